@@ -15,6 +15,12 @@
 //! Append a JSONL entry to the output file:
 //!     `outfile.write_json_line(&json!(struct));`
 //!
+//! Write JSONL entries to stdout instead, for use in a pipeline:
+//!     `OutputSink::stdout().write_json_line(&json!(struct));`
+//!
+//! Parse JSON from a string (or any reader with `deserialize_json_from_reader`):
+//!     `let config: Config = deserialize_json_from_str(&body)?;`
+//!
 //! Read a CSV file into vectors of columns (f64):
 //!     `let data = deserialize_csv_column_vectors_from_path<f64>(&"/path/to/csv/data.csv")`
 //!
@@ -27,23 +33,30 @@
 //! Read a CSV file into a vector of rows (Vec<MyStruct>) (Where each row becomes one object):
 //!     `let data = deserialize_csv_rows_from_path<MyStruct>(&"/path/to/csv/data.csv")`
 //!
+//! Read CSV data from any reader (e.g. an in-memory byte slice):
+//!     `let data = deserialize_csv_rows_from_reader<_, Vec<f64>>(&bytes[..])`
+//!
 
+use crate::precision::SignificantDigits;
 use csv::ReaderBuilder;
 use serde::{Deserialize, Serialize};
 use serde_json;
 use serde_jsonlines::WriteExt;
 use std::clone::Clone;
+use std::collections::{BTreeSet, HashMap, VecDeque};
 use std::ffi::OsStr;
 use std::fs::{File, Metadata, OpenOptions, read_dir};
-use std::io::{BufReader, BufWriter, Write};
+use std::hash::BuildHasher;
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Stdout, Write};
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 #[error("file: `{path}`")]
 pub struct FileIoError<T> {
-    path: Box<Path>,
-    source: T,
+    pub(crate) path: Box<Path>,
+    pub(crate) source: T,
 }
 
 #[derive(Debug, Error)]
@@ -55,23 +68,73 @@ pub enum Error {
     FileIo(#[from] FileIoError<std::io::Error>),
     #[error("parsing error with JSON file")]
     ParseJson(#[from] serde_json::Error),
+    #[error(
+        "parsing error with JSON file: `{}` at line {}, column {}",
+        .0.path.display(),
+        .0.source.line(),
+        .0.source.column()
+    )]
+    ParseJsonFile(#[from] FileIoError<serde_json::Error>),
     #[error("IO error with file: `{path}`: {msg}")]
     Create { path: Box<Path>, msg: String },
     #[error("invalid file or directory:`{path}`: {msg}")]
     InvalidType { path: Box<Path>, msg: String },
     #[error("IO error with file")]
     Fail(#[from] std::io::Error),
+    #[error("parsing error with CSV data")]
+    ReadCsv(#[from] csv::Error),
+    #[error("duplicate CSV header in file: `{path}`: {header}")]
+    DuplicateHeader { path: Box<Path>, header: String },
+    #[error("invalid data shape in file: `{path}`: {msg}")]
+    InvalidShape { path: Box<Path>, msg: String },
+    #[error("invalid value in file: `{path}`: {msg}")]
+    InvalidValue { path: Box<Path>, msg: String },
+    #[error("unexpected CSV header in file: `{path}`: expected {expected:?}, found {found:?}")]
+    HeaderMismatch {
+        path: Box<Path>,
+        expected: Vec<String>,
+        found: Vec<String>,
+    },
+    #[error("missing files in directory: `{path}`: {missing:?}")]
+    MissingFiles {
+        path: Box<Path>,
+        missing: Vec<String>,
+    },
+    #[error("invalid UTF-8 in file: `{path}`: at byte {byte_offset}")]
+    Encoding { path: Box<Path>, byte_offset: usize },
+    #[error("unsupported format for file: `{path}`: {msg}")]
+    UnsupportedFormat { path: Box<Path>, msg: String },
+    #[error("serialization error with file: `{path}`: {msg}")]
+    Serialize { path: Box<Path>, msg: String },
 }
 
-/// Wrapper around a buffered file writer, implementing a method to write json lines.
+/// Wrapper around a buffered writer, implementing a method to write json lines.
+///
+/// `path` names the destination in errors (e.g. `<stdout>` for standard output).
 #[derive(Debug)]
-pub struct OutputFile {
-    writer: BufWriter<File>,
+pub struct OutputSink<W: Write> {
+    writer: BufWriter<W>,
     path: Box<Path>,
 }
 
+/// Wrapper around a buffered file writer, implementing a method to write json lines.
+pub type OutputFile = OutputSink<File>;
+
+impl OutputSink<Stdout> {
+    /// Create a buffered sink writing output to stdout, e.g. for piping into other tools.
+    #[must_use]
+    pub fn stdout() -> Self {
+        OutputSink::from_writer(std::io::stdout(), "<stdout>")
+    }
+}
+
 impl OutputFile {
     /// Create new file for buffered writing of output.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  opening or creating `path` fails.
     pub fn new(path: impl AsRef<Path>) -> Result<OutputFile, Error> {
         let path = path.as_ref();
         let writer = create_buffered_file_writer(path)?;
@@ -82,7 +145,81 @@ impl OutputFile {
         })
     }
 
+    /// Create new file for buffered writing of output, creating any missing parent directories.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  creating a parent directory fails.
+    /// *  opening or creating `path` fails.
+    pub fn new_with_parents(path: impl AsRef<Path>) -> Result<OutputFile, Error> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            create_directory(parent)?;
+        }
+
+        OutputFile::new(path)
+    }
+
+    /// Create new file for buffered writing of output, with the provided permission `mode` (e.g. `0o640`).
+    ///
+    /// The process umask is still applied by the OS when the file is created.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  opening or creating `path` fails.
+    #[cfg(unix)]
+    pub fn new_with_mode(path: impl AsRef<Path>, mode: u32) -> Result<OutputFile, Error> {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let path = path.as_ref();
+        let file = new_file_options()
+            .mode(mode)
+            .open(path)
+            .map_err(|source| FileIoError {
+                path: path.into(),
+                source,
+            })?;
+
+        Ok(OutputFile {
+            writer: BufWriter::new(file),
+            path: path.into(),
+        })
+    }
+}
+
+impl<W: Write> OutputSink<W> {
+    /// Create a buffered sink writing output to any writer, named `name` in errors.
+    pub fn from_writer(writer: W, name: impl AsRef<Path>) -> Self {
+        OutputSink {
+            writer: BufWriter::new(writer),
+            path: name.as_ref().into(),
+        }
+    }
+
+    /// Flushes the `BufWriter` and returns the underlying writer.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  writing fails.
+    pub fn into_inner(self) -> Result<W, Error> {
+        let path = self.path;
+        let writer = self.writer.into_inner().map_err(|e| FileIoError {
+            path,
+            source: e.into_error(),
+        })?;
+
+        Ok(writer)
+    }
+
     /// Appends a JSON line to the `BufWriter`.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  serialization or writing fails.
     pub fn write_json_line<T>(&mut self, jsonl: &T) -> Result<(), Error>
     where
         T: Serialize,
@@ -98,6 +235,11 @@ impl OutputFile {
     }
 
     /// Appends a JSON structure to the `BufWriter`.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  serialization or writing fails.
     pub fn write_json<T>(&mut self, data: &T) -> Result<(), Error>
     where
         T: Serialize,
@@ -110,6 +252,180 @@ impl OutputFile {
 
         Ok(())
     }
+
+    /// Appends a JSON structure to the `BufWriter`, with object keys sorted alphabetically.
+    ///
+    /// Unlike `write_json`, the output doesn't depend on struct field declaration order, which keeps diffs stable.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  serialization or writing fails.
+    pub fn write_json_sorted<T>(&mut self, data: &T) -> Result<(), Error>
+    where
+        T: Serialize,
+    {
+        let mut value = serde_json::to_value(data)?;
+        sort_json_keys(&mut value);
+
+        self.write_json(&value)
+    }
+
+    /// Flushes the `BufWriter` to the underlying writer.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  writing fails.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush().map_err(|source| FileIoError {
+            path: self.path.clone(),
+            source,
+        })?;
+
+        Ok(())
+    }
+}
+
+/// Thread-safe wrapper around `OutputFile`, allowing several threads to append JSON lines to one file.
+///
+/// Each line is written whole while holding a lock, so lines from different threads never interleave.
+#[derive(Debug)]
+pub struct SharedOutputFile {
+    file: Mutex<OutputFile>,
+}
+
+impl SharedOutputFile {
+    /// Create new file for buffered writing of output, shared between threads.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  opening or creating `path` fails.
+    pub fn new(path: impl AsRef<Path>) -> Result<SharedOutputFile, Error> {
+        Ok(SharedOutputFile {
+            file: Mutex::new(OutputFile::new(path)?),
+        })
+    }
+
+    /// Appends a JSON line to the shared `BufWriter`.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  serialization or writing fails.
+    pub fn write_json_line<T>(&self, jsonl: &T) -> Result<(), Error>
+    where
+        T: Serialize,
+    {
+        self.lock().write_json_line(jsonl)
+    }
+
+    /// Flushes the shared `BufWriter` to the file.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  writing fails.
+    pub fn flush(&self) -> Result<(), Error> {
+        self.lock().flush()
+    }
+
+    // A panic in another writer doesn't invalidate the file, so a poisoned lock is recovered.
+    fn lock(&self) -> MutexGuard<'_, OutputFile> {
+        self.file.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Wrapper around `OutputFile` which rolls over to a new numbered file once a size limit would be exceeded.
+///
+/// e.g. `path/to/log.jsonl` produces `path/to/log.0.jsonl`, `path/to/log.1.jsonl`, ...
+#[derive(Debug)]
+pub struct RotatingOutputFile {
+    file: OutputFile,
+    path: Box<Path>,
+    max_bytes: u64,
+    bytes_written: u64,
+    index: usize,
+}
+
+impl RotatingOutputFile {
+    /// Create the first numbered file for buffered writing of output, rolling over after `max_bytes`.
+    ///
+    /// A single line larger than `max_bytes` is still written whole, to its own file.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  opening or creating the first file fails.
+    pub fn new(path: impl AsRef<Path>, max_bytes: u64) -> Result<RotatingOutputFile, Error> {
+        let path: Box<Path> = path.as_ref().into();
+        let file = OutputFile::new(rotated_path(&path, 0))?;
+
+        Ok(RotatingOutputFile {
+            file,
+            path,
+            max_bytes,
+            bytes_written: 0,
+            index: 0,
+        })
+    }
+
+    /// Appends a JSON line, rolling over to the next file first if the line would exceed `max_bytes`.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  serialization or writing fails.
+    /// *  flushing the previous file or creating the next file fails.
+    pub fn write_json_line<T>(&mut self, jsonl: &T) -> Result<(), Error>
+    where
+        T: Serialize,
+    {
+        let mut line = serde_json::to_vec(jsonl)?;
+        line.push(b'\n');
+        let len = line.len() as u64;
+
+        if self.bytes_written > 0 && self.bytes_written + len > self.max_bytes {
+            self.file.flush()?;
+            self.index += 1;
+            self.file = OutputFile::new(rotated_path(&self.path, self.index))?;
+            self.bytes_written = 0;
+        }
+
+        self.file
+            .writer
+            .write_all(&line)
+            .map_err(|source| FileIoError {
+                path: self.file.path.clone(),
+                source,
+            })?;
+        self.bytes_written += len;
+
+        Ok(())
+    }
+
+    /// Flushes the current file.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  writing fails.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.file.flush()
+    }
+}
+
+/// Inserts the rotation index before the extension, e.g. `log.jsonl` becomes `log.3.jsonl`.
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.file_stem().unwrap_or_default().to_os_string();
+    name.push(format!(".{index}"));
+    if let Some(extension) = path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+
+    path.with_file_name(name)
 }
 
 /// Creates a buffered file for writing at the provided path.
@@ -119,9 +435,7 @@ impl OutputFile {
 ///
 /// *  opening or creating `path` fails.
 pub fn create_buffered_file_writer(path: impl AsRef<Path>) -> Result<BufWriter<File>, Error> {
-    let file = OpenOptions::new()
-        .write(true)
-        .create_new(true)
+    let file = new_file_options()
         .open(&path)
         .map_err(|source| FileIoError {
             path: path.as_ref().into(),
@@ -131,6 +445,52 @@ pub fn create_buffered_file_writer(path: impl AsRef<Path>) -> Result<BufWriter<F
     Ok(BufWriter::new(file))
 }
 
+/// Copies the file at `src` to a new file at `dst`, returning the number of bytes copied.
+///
+/// Unlike `std::fs::copy`, errors name whichever of `src` or `dst` failed, e.g. when staging inputs into a run
+/// directory. Like the rest of the crate, an existing `dst` is never overwritten.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening or reading `src` fails.
+/// *  `dst` already exists, or creating or writing it fails.
+pub fn copy_file(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> Result<u64, Error> {
+    let mut reader = BufReader::new(open_file(&src)?);
+    let mut writer = create_buffered_file_writer(&dst)?;
+    let src_err = |source| FileIoError {
+        path: src.as_ref().into(),
+        source,
+    };
+    let dst_err = |source| FileIoError {
+        path: dst.as_ref().into(),
+        source,
+    };
+
+    let mut copied = 0;
+    let mut chunk = [0; 8192];
+    loop {
+        let n = match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(src_err(e).into()),
+        };
+        writer.write_all(&chunk[..n]).map_err(dst_err)?;
+        copied += n as u64;
+    }
+    writer.flush().map_err(dst_err)?;
+
+    Ok(copied)
+}
+
+/// Options for creating a new file for writing, failing if it already exists.
+fn new_file_options() -> OpenOptions {
+    let mut options = OpenOptions::new();
+    let _ = options.write(true).create_new(true);
+    options
+}
+
 /// Create a new directory at the provided path if it doesn't already exist.
 ///
 /// All parents in the path are created as needed.
@@ -172,20 +532,98 @@ pub fn create_directory(path: impl AsRef<Path>) -> Result<(), Error> {
 /// *  path is not a directory.
 /// *  opening or creating `path` fails.
 pub fn create_incremented_directory(path: impl AsRef<Path>) -> Result<PathBuf, Error> {
+    create_incremented_directory_with_fs(&OsFileSystem, path)
+}
+
+/// Create a new directory at the provided path, with the lowest unused numeric suffix, through `fs`.
+///
+/// Behaves as `create_incremented_directory`, but a mock `FileSystem` can be injected, e.g. for tests.
+/// # Errors
+///
+/// Returns an error if:
+/// *  path is not a directory.
+/// *  opening or creating `path` fails.
+pub fn create_incremented_directory_with_fs<F: FileSystem>(
+    fs: &F,
+    path: impl AsRef<Path>,
+) -> Result<PathBuf, Error> {
+    let (_, output_path) = create_indexed_directory(fs, path.as_ref())?;
+
+    Ok(output_path)
+}
+
+/// Provenance metadata written into each run directory by `create_incremented_directory_with_manifest`.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct RunManifest {
+    /// The `n` in `run_n`.
+    pub index: usize,
+    /// Creation time, in seconds since the Unix epoch.
+    pub created: u64,
+    /// The base path the run directory was created in.
+    pub base_path: PathBuf,
+}
+
+/// Create a new directory at the provided path, with the lowest unused numeric suffix, containing a `manifest.json`.
+///
+/// Behaves as `create_incremented_directory`, additionally recording a `RunManifest` for provenance.
+/// # Errors
+///
+/// Returns an error if:
+/// *  path is not a directory.
+/// *  opening or creating `path` fails.
+/// *  writing the manifest fails.
+pub fn create_incremented_directory_with_manifest(
+    path: impl AsRef<Path>,
+) -> Result<PathBuf, Error> {
     let path = path.as_ref();
-    // Create the base directory.
-    create_directory(path)?;
+    let (index, output_path) = create_indexed_directory(&OsFileSystem, path)?;
+    // A clock before the epoch is nonsensical, record it as zero.
+    let created = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let manifest = RunManifest {
+        index,
+        created,
+        base_path: path.into(),
+    };
+    serialize_json_to_path(&manifest, output_path.join("manifest.json"))?;
+
+    Ok(output_path)
+}
+
+/// Creates the `run_n` directory with the lowest unused `n`, returning `n` and the new path.
+fn create_indexed_directory<F: FileSystem>(fs: &F, path: &Path) -> Result<(usize, PathBuf), Error> {
+    // Create the base directory, if it doesn't already exist.
+    if matches!(fs.try_exists(path), Ok(true)) {
+        let is_dir = fs.is_dir(path).map_err(|source| FileIoError {
+            path: path.into(),
+            source,
+        })?;
+        if !is_dir {
+            return Err(Error::InvalidType {
+                path: path.into(),
+                msg: "attempt to open file as a directory".to_string(),
+            });
+        }
+    } else {
+        fs.create_dir_all(path).map_err(|source| FileIoError {
+            path: path.into(),
+            source,
+        })?;
+    }
     // This is excessive, but we abort on the first failed `create_dir_all` call.
     for i in 0..usize::MAX {
         // Generate the next run iteration.
         let output_path = path.join(format!("run_{i}"));
+        // Errors name the exact `run_n` path, with the underlying IO error (e.g. `PermissionDenied`) as the source.
+        let map_err = |source| FileIoError {
+            path: output_path.as_path().into(),
+            source,
+        };
         // If it doesn't already exist, try to create it.
-        if let Ok(false) = Path::new(&output_path).try_exists() {
-            std::fs::create_dir_all(&output_path).map_err(|source| FileIoError {
-                path: path.into(),
-                source,
-            })?;
-            return Ok(output_path);
+        if !fs.try_exists(&output_path).map_err(map_err)? {
+            fs.create_dir_all(&output_path).map_err(map_err)?;
+            return Ok((i, output_path));
         }
     }
     // Couldn't create the directory, give up.
@@ -195,264 +633,4678 @@ pub fn create_incremented_directory(path: impl AsRef<Path>) -> Result<PathBuf, E
     })
 }
 
-/// Serialize data from a data object to a new file at provided path.
-pub fn serialize_json_to_path<T>(data: &T, path: impl AsRef<Path>) -> Result<(), Error>
-where
-    T: Serialize,
-{
-    // Create a new file for the output.
-    let mut file = OutputFile::new(path)?;
-    // Write the contents of the data object as json to the output file.
-    file.write_json(data)?;
-
-    Ok(())
-}
+/// The filesystem operations used by `create_incremented_directory_with_fs`, so they can be replaced in tests.
+pub trait FileSystem {
+    /// Creates a directory and all missing parents, as `std::fs::create_dir_all`.
+    /// # Errors
+    ///
+    /// Returns an error if creating any of the directories fails.
+    fn create_dir_all(&self, path: &Path) -> std::io::Result<()>;
 
-/// Deserialize json data from a provided path into appropriate data object.
-pub fn deserialize_json_from_path<T>(path: impl AsRef<Path>) -> Result<T, Error>
-where
-    T: for<'a> Deserialize<'a>,
-{
-    // Open the file containing the data.
-    let file = open_file(&path)?;
-    let reader = BufReader::new(file);
-    // Read the contents of the file as an instance of the appropriate deserialized structure.
-    let out = serde_json::from_reader(reader)?;
+    /// Returns whether `path` exists, as `Path::try_exists`.
+    /// # Errors
+    ///
+    /// Returns an error if existence can't be determined.
+    fn try_exists(&self, path: &Path) -> std::io::Result<bool>;
 
-    Ok(out)
+    /// Returns whether the metadata of `path` is that of a directory.
+    /// # Errors
+    ///
+    /// Returns an error if reading the metadata fails.
+    fn is_dir(&self, path: &Path) -> std::io::Result<bool>;
 }
 
-/// Opens a file or directory in read-only mode from provided path.
-fn open(path: impl AsRef<Path>) -> Result<(File, Metadata), Error> {
-    // Opens file from path
-    let file = File::open(&path).map_err(|source| FileIoError {
-        path: path.as_ref().into(),
-        source,
-    })?;
-
-    let metadata = file.metadata()?;
+/// The real filesystem, through `std::fs`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsFileSystem;
 
-    Ok((file, metadata))
-}
+impl FileSystem for OsFileSystem {
+    fn create_dir_all(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(path)
+    }
 
-// Opens a file in read-only mode from provided path.
-/// # Errors
-///
-/// Returns an error if:
-///
-/// * The user lacks permissions to perform `metadata` call on `path`.
-/// * The user lacks permissions to perform `open()` call on `path`.
-/// * `path` does not exist.
-/// * `path` is not a file.
-pub fn open_file(path: impl AsRef<Path>) -> Result<File, Error> {
-    let (file, metadata) = open(&path)?;
+    fn try_exists(&self, path: &Path) -> std::io::Result<bool> {
+        path.try_exists()
+    }
 
-    if metadata.is_file() {
-        Ok(file)
-    } else {
-        Err(Error::InvalidType {
-            path: path.as_ref().into(),
-            msg: "attempt to open directory as a file".to_string(),
-        })
+    fn is_dir(&self, path: &Path) -> std::io::Result<bool> {
+        Ok(std::fs::metadata(path)?.is_dir())
     }
 }
 
-// Opens a directory in read-only mode from provided path.
+/// Checks that the directory at the provided path contains each of the `required` files, e.g. a complete run.
 /// # Errors
 ///
 /// Returns an error if:
 ///
-/// * The user lacks permissions to perform `metadata` call on `path`.
-/// * The user lacks permissions to perform `open()` call on `path`.
-/// * `path` does not exist.
-/// * `path` is not a directory.
-pub fn open_dir(path: impl AsRef<Path>) -> Result<File, Error> {
-    let (dir, metadata) = open(&path)?;
+/// *  `path` is not a directory, or opening it fails.
+/// *  any of `required` is absent or not a regular file, listing all of them.
+pub fn validate_run_directory(path: impl AsRef<Path>, required: &[&str]) -> Result<(), Error> {
+    let path = path.as_ref();
+    let _ = open_dir(path)?;
 
-    if metadata.is_dir() {
-        Ok(dir)
-    } else {
-        Err(Error::InvalidType {
-            path: path.as_ref().into(),
-            msg: "attempt to open file as a directory".to_string(),
-        })
+    let missing: Vec<String> = required
+        .iter()
+        .filter(|name| !matches!(open(path.join(name)), Ok((_, metadata)) if metadata.is_file()))
+        .map(ToString::to_string)
+        .collect();
+    if !missing.is_empty() {
+        return Err(Error::MissingFiles {
+            path: path.into(),
+            missing,
+        });
     }
+
+    Ok(())
 }
 
-/// Deserialize CSV data from a provided path into a vector.
+/// Builds a directory of outputs in a temporary sibling, then publishes it at `final_path`, replacing any prior one.
 ///
-/// Each row of the CSV is deserialized into the user supplied `_data_type`
-/// Requires the CSV to be standard, with a header value for each field (matching the `_data_type` if it is struct).
+/// `build_fn` is given the temporary directory to populate. If it fails, the temporary directory is removed and
+/// `final_path` is left untouched. Readers never observe a partially built directory: the prior directory is first
+/// moved aside, then the new one renamed into place, so `final_path` is briefly absent between the two renames.
+/// If the new directory cannot be renamed into place, the prior one is restored.
 /// # Errors
 ///
 /// Returns an error if:
 ///
-/// *  opening `path` fails.
-/// *  serialization fails.
-pub fn deserialize_csv_rows_from_path<T>(path: impl AsRef<Path>) -> Result<Vec<T>, Error>
+/// *  `final_path` has no parent or file name.
+/// *  creating the temporary directory, or renaming either directory, fails.
+/// *  `build_fn` fails.
+pub fn atomic_replace_directory<F>(final_path: impl AsRef<Path>, build_fn: F) -> Result<(), Error>
 where
-    T: for<'a> Deserialize<'a> + Clone,
+    F: FnOnce(&Path) -> Result<(), Error>,
 {
-    // Open the file containing the data.
-    let file = open_file(&path)?;
-    // Setup the reading of the CSV file
-    let mut reader = ReaderBuilder::new()
-        .has_headers(true) // CSV header is expected.
-        .comment(Some(b'#')) // Comment lines start with '#'.
-        .flexible(false) // All rows must have the same number of fields.
-        .delimiter(b',') // Entries are comma separated (actual CSV).
-        .from_reader(file);
+    let final_path = final_path.as_ref();
+    let Some(name) = final_path.file_name() else {
+        return Err(Error::InvalidType {
+            path: final_path.into(),
+            msg: "expected a directory path with a final component".to_string(),
+        });
+    };
+    // Siblings share a filesystem with `final_path`, so renames are atomic.
+    let sibling = |suffix: &str| {
+        let mut sibling_name = std::ffi::OsString::from(".");
+        sibling_name.push(name);
+        sibling_name.push(format!(".{suffix}-{}", std::process::id()));
+        final_path.with_file_name(sibling_name)
+    };
+    let temp_path = sibling("tmp");
+    let old_path = sibling("old");
 
-    let mut out = vec![];
+    std::fs::create_dir(&temp_path).map_err(|source| FileIoError {
+        path: temp_path.as_path().into(),
+        source,
+    })?;
+    if let Err(e) = build_fn(&temp_path) {
+        // Best effort, the build error is more useful than any cleanup error.
+        let _ = std::fs::remove_dir_all(&temp_path);
+        return Err(e);
+    }
 
-    // Deserialize the CSV into column vectors.
-    for result in reader.deserialize::<T>() {
-        let result: T = result.map_err(|source| FileIoError {
-            path: path.as_ref().into(),
+    let replacing = matches!(final_path.try_exists(), Ok(true));
+    if replacing && let Err(source) = std::fs::rename(final_path, &old_path) {
+        // Best effort, as for the build error.
+        let _ = std::fs::remove_dir_all(&temp_path);
+        return Err(FileIoError {
+            path: final_path.into(),
             source,
-        })?;
-        out.push(result);
+        }
+        .into());
+    }
+    if let Err(source) = std::fs::rename(&temp_path, final_path) {
+        // Best effort, restore the prior directory rather than leave it stranded under a hidden name.
+        if replacing {
+            let _ = std::fs::rename(&old_path, final_path);
+        }
+        let _ = std::fs::remove_dir_all(&temp_path);
+        return Err(FileIoError {
+            path: temp_path.as_path().into(),
+            source,
+        }
+        .into());
+    }
+    if replacing {
+        // Best effort, the new directory is already published.
+        let _ = std::fs::remove_dir_all(&old_path);
     }
 
-    Ok(out)
+    Ok(())
 }
 
-/// Matrix transposition.
+/// Serialize data from a data object to a new file at provided path.
+/// # Errors
 ///
-/// Returns a transposed copy of the original matrix. Works with slices.
-/// Inner values are cloned, so can be expensive.
-fn transpose<T>(matrix: &[Vec<T>]) -> Vec<Vec<T>>
+/// Returns an error if:
+///
+/// *  creating `path` fails.
+/// *  serialization fails.
+pub fn serialize_json_to_path<T>(data: &T, path: impl AsRef<Path>) -> Result<(), Error>
 where
-    T: Clone,
+    T: Serialize,
 {
-    if matrix.is_empty() {
-        return matrix.to_vec();
-    }
-    // Create the empty transposed array.
-    let mut transposed = vec![vec![]; matrix[0].len()];
-    // Fill the transposed array by copying from the matrix.
-    for original_row in matrix {
-        for (item, transposed_row) in original_row.iter().zip(&mut transposed) {
-            transposed_row.push(item.clone());
-        }
-    }
+    // Create a new file for the output.
+    let mut file = OutputFile::new(path)?;
+    // Write the contents of the data object as json to the output file.
+    file.write_json(data)?;
 
-    transposed
+    Ok(())
 }
 
-/// Deserialize n-dimensional CSV data from a provided path into nested Vectors.
+/// Serialize data from a data object to a new JSON file at provided path, with floats rounded to `digits`
+/// significant digits.
 ///
-/// Each column vector represents a column of the CSV.
-/// Requires the CSV to be standard, with a header value for each field.
-/// All fields must be of the same type.
-/// The data type must be specified by the caller.
-pub fn deserialize_csv_column_vectors_from_path<T>(
+/// Avoids bloating the output with meaningless round-off, e.g. `0.1 + 0.2` is written as `0.3`.
+/// Integers and strings are written unchanged.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  creating `path` fails.
+/// *  serialization fails.
+pub fn serialize_json_with_precision_to_path<T>(
+    data: &T,
     path: impl AsRef<Path>,
-) -> Result<Vec<Vec<T>>, Error>
+    digits: usize,
+) -> Result<(), Error>
 where
-    T: for<'a> Deserialize<'a> + Clone,
+    T: Serialize,
 {
-    let new = deserialize_csv_rows_from_path::<Vec<T>>(path)?;
-
-    Ok(transpose(&new))
+    serialize_json_to_path(&SignificantDigits::new(data, digits), path)
 }
 
-/// Returns a `Vector<PathBuf>` containing all files within the provided directory.
+/// Serialize rows to a new CSV file at provided path, with floats rounded to `digits` significant digits.
+///
+/// Integers and strings are written unchanged.
 /// # Errors
 ///
 /// Returns an error if:
 ///
-/// *  opening `path` fails.
-pub fn collect_files_from_dir_path(path: impl AsRef<Path>) -> Result<Vec<PathBuf>, Error> {
-    let mut out = vec![];
-    let dir_entries = read_dir(&path).map_err(|source| FileIoError {
+/// *  creating `path` fails.
+/// *  serialization fails.
+pub fn serialize_csv_rows_with_precision_to_path<T>(
+    rows: &[T],
+    path: impl AsRef<Path>,
+    digits: usize,
+) -> Result<(), Error>
+where
+    T: Serialize,
+{
+    let map_err = |source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    };
+    let mut writer = csv::Writer::from_writer(create_buffered_file_writer(&path)?);
+    for row in rows {
+        writer
+            .serialize(SignificantDigits::new(row, digits))
+            .map_err(map_err)?;
+    }
+    writer.flush().map_err(|source| FileIoError {
         path: path.as_ref().into(),
         source,
     })?;
 
-    for file in dir_entries {
-        let file = file.map_err(|source| FileIoError {
-            path: path.as_ref().into(),
+    Ok(())
+}
+
+/// Updates the JSON file at provided path in place: deserializes it, applies `f`, and writes the result back.
+///
+/// The result is written to a temporary sibling and renamed over `path`, so the file is never left partially
+/// written, e.g. for updating a single field of a config.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  `path` does not exist, or has no file name.
+/// *  opening `path` fails.
+/// *  deserialization or serialization fails.
+/// *  creating the temporary file, or renaming it, fails.
+pub fn update_json_in_place<T, F>(path: impl AsRef<Path>, f: F) -> Result<(), Error>
+where
+    T: Serialize + for<'a> Deserialize<'a>,
+    F: FnOnce(&mut T),
+{
+    let path = path.as_ref();
+    let Some(name) = path.file_name() else {
+        return Err(Error::InvalidType {
+            path: path.into(),
+            msg: "expected a file path with a final component".to_string(),
+        });
+    };
+    if !matches!(path.try_exists(), Ok(true)) {
+        return Err(Error::InvalidType {
+            path: path.into(),
+            msg: "no JSON file to update".to_string(),
+        });
+    }
+
+    let mut data: T = deserialize_json_from_path(path)?;
+    f(&mut data);
+
+    // A sibling shares a filesystem with `path`, so the rename is atomic.
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(name);
+    temp_name.push(format!(".tmp-{}", std::process::id()));
+    let temp_path = path.with_file_name(temp_name);
+    let written = OutputFile::new(&temp_path).and_then(|mut file| {
+        file.write_json(&data)?;
+        file.flush()
+    });
+    if let Err(e) = written {
+        // Best effort, the write error is more useful than any cleanup error.
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e);
+    }
+    if let Err(source) = std::fs::rename(&temp_path, path) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(FileIoError {
+            path: temp_path.as_path().into(),
             source,
-        })?;
+        }
+        .into());
+    }
 
-        // Only process files, not nested directories, etc.
-        if file.metadata()?.is_file() {
-            out.push(file.path());
+    Ok(())
+}
+
+/// Serialization format written by `serialize_multi_format`.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Format {
+    /// Pretty-printed JSON, with extension ".json".
+    Json,
+    /// YAML, with extension ".yaml". Requires the `yaml` feature.
+    Yaml,
+    /// TOML, with extension ".toml". Requires the `toml` feature.
+    Toml,
+    /// `MessagePack` with named fields, with extension ".msgpack". Requires the `msgpack` feature.
+    MsgPack,
+}
+
+impl Format {
+    /// The file extension for the format.
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+            Self::Toml => "toml",
+            Self::MsgPack => "msgpack",
         }
     }
 
-    Ok(out)
+    /// The feature the format requires, if it is not enabled.
+    fn missing_feature(self) -> Option<&'static str> {
+        match self {
+            Self::Json => None,
+            Self::Yaml => (!cfg!(feature = "yaml")).then_some("yaml"),
+            Self::Toml => (!cfg!(feature = "toml")).then_some("toml"),
+            Self::MsgPack => (!cfg!(feature = "msgpack")).then_some("msgpack"),
+        }
+    }
+
+    /// Serializes `data` in the format, JSON pretty-printed.
+    fn to_bytes<T>(self, data: &T) -> Result<Vec<u8>, String>
+    where
+        T: Serialize,
+    {
+        match self {
+            Self::Json => serde_json::to_vec_pretty(data).map_err(|e| e.to_string()),
+            #[cfg(feature = "yaml")]
+            Self::Yaml => serde_norway::to_string(data)
+                .map(String::into_bytes)
+                .map_err(|e| e.to_string()),
+            #[cfg(feature = "toml")]
+            Self::Toml => toml::to_string_pretty(data)
+                .map(String::into_bytes)
+                .map_err(|e| e.to_string()),
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => rmp_serde::to_vec_named(data).map_err(|e| e.to_string()),
+            #[cfg(not(all(feature = "yaml", feature = "toml", feature = "msgpack")))]
+            _ => Err(format!("the `{}` feature is not enabled", self.extension())),
+        }
+    }
 }
 
-/// Deserializes n-dimensional data from all CSV (".csv") files in a provided directory path into one nested Vector.
-pub fn deserialize_csv_rows_from_dir_path<T>(
-    path: impl AsRef<Path>,
-) -> Result<Vec<Vec<Vec<T>>>, Error>
+/// Serialize data from a data object to a new file per format, e.g. `base.json` and `base.msgpack`.
+///
+/// The extension of each file replaces any extension of `base_path`. Every format is checked to be enabled before
+/// any file is written. Returns the paths written, in the order of `formats`.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  the feature for any of `formats` is not enabled.
+/// *  creating any of the files fails.
+/// *  serialization fails.
+pub fn serialize_multi_format<T>(
+    data: &T,
+    base_path: &Path,
+    formats: &[Format],
+) -> Result<Vec<PathBuf>, Error>
 where
-    T: for<'a> Deserialize<'a> + Clone,
+    T: Serialize,
 {
-    collect_files_from_dir_path(path)?
+    if let Some((format, feature)) = formats
         .iter()
-        .filter(|file| file.extension() == Some(OsStr::new("csv")))
-        .map(|file| deserialize_csv_column_vectors_from_path::<T>(file))
-        .collect()
+        .find_map(|&format| format.missing_feature().map(|feature| (format, feature)))
+    {
+        return Err(Error::UnsupportedFormat {
+            path: base_path.with_extension(format.extension()).into(),
+            msg: format!("{format:?} requires the `{feature}` feature"),
+        });
+    }
+
+    let mut out = Vec::with_capacity(formats.len());
+    for &format in formats {
+        let path = base_path.with_extension(format.extension());
+        let bytes = format.to_bytes(data).map_err(|msg| Error::Serialize {
+            path: path.as_path().into(),
+            msg,
+        })?;
+        let mut writer = create_buffered_file_writer(&path)?;
+        writer
+            .write_all(&bytes)
+            .and_then(|()| writer.flush())
+            .map_err(|source| FileIoError {
+                path: path.as_path().into(),
+                source,
+            })?;
+        out.push(path);
+    }
+
+    Ok(out)
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    #[test]
-    fn test_deserialize_csv_rows_from_path() {
-        let expected = vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0], vec![
-            7.0, 8.0, 9.0,
-        ]];
-        let result = deserialize_csv_rows_from_path::<Vec<f64>>("tests/example1.csv").unwrap();
-        assert_eq!(expected, result);
+/// Serialize data from a data object to a file at provided path, first moving any existing file to a backup.
+///
+/// `file.json` is backed up as `file.json.bak`, or `file.json.bak.1`, `file.json.bak.2`, etc. if earlier backups
+/// exist, so no backup is ever overwritten. Returns the path of the backup, if one was made.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  renaming the existing file fails.
+/// *  creating `path` fails.
+/// *  serialization fails.
+pub fn backup_then_write_json<T>(data: &T, path: impl AsRef<Path>) -> Result<Option<PathBuf>, Error>
+where
+    T: Serialize,
+{
+    let path = path.as_ref();
+    let backup = if path.exists() {
+        let mut name = path.as_os_str().to_owned();
+        name.push(".bak");
+        let mut backup = PathBuf::from(&name);
+        let mut index = 0;
+        while backup.exists() {
+            index += 1;
+            backup = PathBuf::from(format!("{}.{index}", name.to_string_lossy()));
+        }
+        std::fs::rename(path, &backup).map_err(|source| FileIoError {
+            path: path.into(),
+            source,
+        })?;
+        Some(backup)
+    } else {
+        None
+    };
+    serialize_json_to_path(data, path)?;
+
+    Ok(backup)
+}
+
+/// Serialize data from a data object to a new file at provided path, with object keys sorted alphabetically.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  creating `path` fails.
+/// *  serialization fails.
+pub fn serialize_json_sorted_to_path<T>(data: &T, path: impl AsRef<Path>) -> Result<(), Error>
+where
+    T: Serialize,
+{
+    let mut file = OutputFile::new(path)?;
+    file.write_json_sorted(data)?;
+
+    Ok(())
+}
+
+/// Serialize rows to new CSV files of at most `rows_per_file` rows each, e.g. for tools with row limits.
+///
+/// `path/to/base.csv` produces `path/to/base_0.csv`, `path/to/base_1.csv`, etc., each with its own header row.
+/// Returns the paths of the created files, in order. No files are created for empty `rows`.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  `rows_per_file` is zero, or `base_path` has no file name.
+/// *  creating a shard fails, e.g. it already exists.
+/// *  serialization fails.
+pub fn serialize_csv_rows_sharded_to_path<T: Serialize>(
+    rows: &[T],
+    base_path: &Path,
+    rows_per_file: usize,
+) -> Result<Vec<PathBuf>, Error> {
+    let invalid_value = |msg: &str| Error::InvalidValue {
+        path: base_path.into(),
+        msg: msg.to_string(),
+    };
+    if rows_per_file == 0 {
+        return Err(invalid_value("expected at least one row per file"));
     }
+    let stem = base_path
+        .file_stem()
+        .ok_or_else(|| invalid_value("expected a file name"))?
+        .to_string_lossy();
+    let extension = base_path
+        .extension()
+        .map_or("csv".into(), OsStr::to_string_lossy);
 
-    #[test]
-    #[should_panic]
-    fn test_deserialize_csv_rows_from_path_malformed() {
-        // Data values are floats, attempt to serialize to unsigned ints will fail.
-        let e = deserialize_csv_rows_from_path::<Vec<u64>>("tests/example1.csv");
-        dbg!(&e);
-        let _ = e.unwrap();
+    let mut paths = vec![];
+    for (i, chunk) in rows.chunks(rows_per_file).enumerate() {
+        let path = base_path.with_file_name(format!("{stem}_{i}.{extension}"));
+        let map_err = |source| FileIoError {
+            path: path.as_path().into(),
+            source,
+        };
+        // A new writer per shard repeats the header.
+        let mut writer = csv::Writer::from_writer(create_buffered_file_writer(&path)?);
+        for row in chunk {
+            writer.serialize(row).map_err(map_err)?;
+        }
+        writer.flush().map_err(|source| FileIoError {
+            path: path.as_path().into(),
+            source,
+        })?;
+        paths.push(path);
     }
 
-    #[test]
-    #[should_panic]
-    fn test_deserialize_csv_rows_from_path_eexist() {
-        // Path does not exist.
-        let e = deserialize_csv_rows_from_path::<Vec<f64>>("tests/non_exist");
-        dbg!(&e);
-        let _ = e.unwrap();
+    Ok(paths)
+}
+
+/// Serialize rows to the end of the CSV at provided path, creating it if needed.
+///
+/// A header is written only if the file is new or empty, so a long-running job can append results periodically.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  `path` is a directory.
+/// *  opening or writing `path` fails.
+/// *  serialization fails.
+pub fn append_csv_rows_to_path<T: Serialize>(
+    rows: &[T],
+    path: impl AsRef<Path>,
+) -> Result<(), Error> {
+    let path = path.as_ref();
+    let map_err = |source| FileIoError {
+        path: path.into(),
+        source,
+    };
+    if path.is_dir() {
+        return Err(Error::InvalidType {
+            path: path.into(),
+            msg: "attempt to append to a directory as a file".to_string(),
+        });
     }
+    let file = OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)
+        .map_err(map_err)?;
+    let is_empty = file.metadata().map_err(map_err)?.len() == 0;
 
-    #[test]
-    #[should_panic]
-    fn test_deserialize_csv_rows_from_path_missing() {
-        // Missing field in one row.
-        let e = deserialize_csv_rows_from_path::<Vec<f64>>("tests/bad/missing_field.csv");
-        dbg!(&e);
-        let _ = e.unwrap();
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(is_empty)
+        .from_writer(BufWriter::new(file));
+    for row in rows {
+        writer.serialize(row).map_err(|source| FileIoError {
+            path: path.into(),
+            source,
+        })?;
     }
+    writer.flush().map_err(map_err)?;
 
-    #[test]
-    #[should_panic]
-    fn test_deserialize_csv_rows_from_path_nonfile() {
-        // `test_dir` is a directory, not a file.
-        let e = deserialize_csv_rows_from_path::<Vec<f64>>("tests");
-        dbg!(&e);
-        let _ = e.unwrap();
+    Ok(())
+}
+
+/// Serialize a matrix of rows to a new `NumPy` `.npy` file at provided path, as a 2-D little-endian float64 array.
+///
+/// Lossless and fast to load with `numpy.load`. Requires the `npy` feature.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  the rows of `matrix` differ in length, in which case nothing is written.
+/// *  creating or writing `path` fails.
+#[cfg(feature = "npy")]
+pub fn serialize_matrix_to_npy(matrix: &[Vec<f64>], path: impl AsRef<Path>) -> Result<(), Error> {
+    let columns = matrix.first().map_or(0, Vec::len);
+    if let Some(row) = matrix.iter().position(|row| row.len() != columns) {
+        return Err(Error::InvalidShape {
+            path: path.as_ref().into(),
+            msg: format!(
+                "expected {columns} values in row {row}, found {}",
+                matrix[row].len()
+            ),
+        });
     }
 
-    #[test]
-    fn test_deserialize_csv_rows_from_dir_path() {
-        //
-        let e = deserialize_csv_rows_from_dir_path::<f64>("tests");
-        dbg!(&e);
-        let _ = e.unwrap();
+    // Format version 1.0: magic string, version, header length, then a header dict padded with spaces and
+    // terminated by a newline so the data starts on a multiple of 64 bytes.
+    let mut header = format!(
+        "{{'descr': '<f8', 'fortran_order': False, 'shape': ({}, {columns}), }}",
+        matrix.len()
+    );
+    let unpadded = 10 + header.len() + 1;
+    header.push_str(&" ".repeat(unpadded.next_multiple_of(64) - unpadded));
+    header.push('\n');
+    let header_len = u16::try_from(header.len()).map_err(|_| Error::InvalidShape {
+        path: path.as_ref().into(),
+        msg: "shape too large for an npy header".to_string(),
+    })?;
+
+    let mut writer = create_buffered_file_writer(&path)?;
+    let mut write = |bytes: &[u8]| {
+        writer.write_all(bytes).map_err(|source| FileIoError {
+            path: path.as_ref().into(),
+            source,
+        })
+    };
+    write(b"\x93NUMPY\x01\x00")?;
+    write(&header_len.to_le_bytes())?;
+    write(header.as_bytes())?;
+    for value in matrix.iter().flatten() {
+        write(&value.to_le_bytes())?;
+    }
+    writer.flush().map_err(|source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    })?;
+
+    Ok(())
+}
+
+/// Merges CSV files, each already sorted by the numeric column `key_col`, into one new sorted CSV at `output`.
+///
+/// A streaming k-way merge: only the current row of each input is held in memory. The inputs must share the
+/// same header, which is written once, and must individually be sorted by the key (this is not checked).
+/// Rows with equal keys keep the order of `inputs`.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening any of `inputs` fails, or their headers differ.
+/// *  a key is not a number, or `key_col` is out of range.
+/// *  creating or writing `output` fails.
+pub fn merge_sorted_csv_by_column(
+    inputs: &[&Path],
+    key_col: usize,
+    output: &Path,
+) -> Result<(), Error> {
+    let mut readers = vec![];
+    for &path in inputs {
+        readers.push((path, CsvReader::default().csv_reader(open_file(path)?)));
+    }
+    let mut headers: Option<csv::StringRecord> = None;
+    for (path, reader) in &mut readers {
+        let found = reader.headers().map_err(|source| FileIoError {
+            path: (*path).into(),
+            source,
+        })?;
+        match &headers {
+            Some(expected) if expected != found => {
+                return Err(Error::HeaderMismatch {
+                    path: (*path).into(),
+                    expected: expected.iter().map(ToString::to_string).collect(),
+                    found: found.iter().map(ToString::to_string).collect(),
+                });
+            }
+            Some(_) => {}
+            None => headers = Some(found.clone()),
+        }
+    }
+
+    let mut heads = vec![];
+    for (path, reader) in &mut readers {
+        heads.push(next_keyed_record(path, reader, key_col)?);
+    }
+
+    let map_err = |source| FileIoError {
+        path: output.into(),
+        source,
+    };
+    let mut writer = csv::Writer::from_writer(create_buffered_file_writer(output)?);
+    if let Some(headers) = &headers {
+        writer.write_record(headers).map_err(map_err)?;
+    }
+    // The input with the lowest key, the first one on ties.
+    while let Some(i) = heads
+        .iter()
+        .enumerate()
+        .filter_map(|(i, head)| head.as_ref().map(|(key, _)| (i, key)))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(i, _)| i)
+    {
+        if let Some((_, record)) = &heads[i] {
+            writer.write_record(record).map_err(map_err)?;
+        }
+        let (path, reader) = &mut readers[i];
+        heads[i] = next_keyed_record(path, reader, key_col)?;
+    }
+    writer.flush().map_err(|source| FileIoError {
+        path: output.into(),
+        source,
+    })?;
+
+    Ok(())
+}
+
+/// Reads the next record of a CSV, with its numeric key in column `key_col`, or `None` at the end.
+fn next_keyed_record<R: Read>(
+    path: &Path,
+    reader: &mut csv::Reader<R>,
+    key_col: usize,
+) -> Result<Option<(f64, csv::StringRecord)>, Error> {
+    let mut record = csv::StringRecord::new();
+    let more = reader
+        .read_record(&mut record)
+        .map_err(|source| FileIoError {
+            path: path.into(),
+            source,
+        })?;
+    if !more {
+        return Ok(None);
+    }
+    let key = record
+        .get(key_col)
+        .and_then(|cell| cell.trim().parse::<f64>().ok())
+        .ok_or_else(|| Error::InvalidValue {
+            path: path.into(),
+            msg: format!(
+                "expected a numeric key in column {key_col}, found {:?}",
+                record.get(key_col)
+            ),
+        })?;
+
+    Ok(Some((key, record)))
+}
+
+/// Recursively sorts the keys of all objects within a JSON value.
+fn sort_json_keys(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            map.sort_keys();
+            map.values_mut().for_each(sort_json_keys);
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(sort_json_keys),
+        _ => {}
+    }
+}
+
+/// Deserialize json data from a provided path into appropriate data object.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  deserialization fails.
+pub fn deserialize_json_from_path<T>(path: impl AsRef<Path>) -> Result<T, Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    // Open the file containing the data.
+    let file = open_file(&path)?;
+    let reader = BufReader::new(file);
+
+    // Attach the path, so the failing file can be identified.
+    let out = serde_json::from_reader(reader).map_err(|source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    })?;
+
+    Ok(out)
+}
+
+/// Deserialize json data from any reader into appropriate data object.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  reading fails.
+/// *  deserialization fails.
+pub fn deserialize_json_from_reader<R, T>(reader: R) -> Result<T, Error>
+where
+    R: Read,
+    T: for<'a> Deserialize<'a>,
+{
+    // Read the contents as an instance of the appropriate deserialized structure.
+    let out = serde_json::from_reader(reader)?;
+
+    Ok(out)
+}
+
+/// Deserialize json data from a string into appropriate data object.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  deserialization fails.
+pub fn deserialize_json_from_str<T>(s: &str) -> Result<T, Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    let out = serde_json::from_str(s)?;
+
+    Ok(out)
+}
+
+/// Deserialize the last `n` records of a JSON lines file at provided path, in file order.
+///
+/// The file is read backwards in chunks from its end, so only the tail of a large log is read.
+/// Fewer records are returned if the file has fewer than `n`. Blank lines are skipped.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening or reading `path` fails.
+/// *  deserialization of any of the last `n` records fails.
+pub fn tail_jsonl<T>(path: impl AsRef<Path>, n: usize) -> Result<Vec<T>, Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    const CHUNK_BYTES: u64 = 8192;
+
+    let map_err = |source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    };
+    let records = |buffer: &[u8]| -> Vec<usize> {
+        // Start offsets of the non-blank lines.
+        let mut starts = vec![];
+        let mut start = 0;
+        for line in buffer.split(|&b| b == b'\n') {
+            if !line.trim_ascii().is_empty() {
+                starts.push(start);
+            }
+            start += line.len() + 1;
+        }
+        starts
+    };
+
+    let mut file = open_file(&path)?;
+    let mut position = file.metadata().map_err(map_err)?.len();
+    let mut buffer: Vec<u8> = vec![];
+    // Non-blank lines known to be complete, i.e. after the first newline in `buffer`, which may be partial.
+    let mut complete = 0;
+    while n > 0 && position > 0 && complete < n {
+        let size = CHUNK_BYTES.min(position);
+        position -= size;
+        let mut chunk = vec![0; usize::try_from(size).unwrap_or(usize::MAX)];
+        let _ = file.seek(SeekFrom::Start(position)).map_err(map_err)?;
+        file.read_exact(&mut chunk).map_err(map_err)?;
+
+        // Only the new chunk is scanned, its last line continues the partial first line of `buffer`.
+        let head = buffer.split(|&b| b == b'\n').next().unwrap_or_default();
+        let mut lines = chunk.split(|&b| b == b'\n').skip(1).peekable();
+        while let Some(line) = lines.next() {
+            let blank = line.trim_ascii().is_empty()
+                && (lines.peek().is_some() || head.trim_ascii().is_empty());
+            complete += usize::from(!blank);
+        }
+        chunk.extend_from_slice(&buffer);
+        buffer = chunk;
+    }
+
+    let starts = records(&buffer);
+    starts[starts.len().saturating_sub(n)..]
+        .iter()
+        .map(|&start| {
+            let line = buffer[start..]
+                .split(|&b| b == b'\n')
+                .next()
+                .unwrap_or_default();
+            serde_json::from_slice(line).map_err(|source| {
+                FileIoError {
+                    path: path.as_ref().into(),
+                    source,
+                }
+                .into()
+            })
+        })
+        .collect()
+}
+
+/// Deserialize json data from a provided path into a generic JSON `Value`, keeping the original order of object keys.
+///
+/// Writing the (modified) value back with `serialize_json_to_path` then preserves the key order, keeping diffs small.
+/// Requires the `preserve_order` feature, which switches `serde_json` objects to insertion order crate-wide.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  deserialization fails.
+#[cfg(feature = "preserve_order")]
+pub fn deserialize_json_preserving_order_from_path(
+    path: impl AsRef<Path>,
+) -> Result<serde_json::Value, Error> {
+    deserialize_json_from_path(path)
+}
+
+/// Deserialize layered JSON files into one data object, e.g. defaults followed by overrides.
+///
+/// Files are deep-merged in order: later files override earlier keys, objects are merged recursively,
+/// and any other value (including arrays) is replaced outright.
+/// With `skip_missing`, paths that don't exist are ignored rather than reported as an error.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening any of `paths` fails.
+/// *  deserialization fails.
+pub fn deserialize_json_merged_from_paths<T>(
+    paths: &[&Path],
+    skip_missing: bool,
+) -> Result<T, Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    let mut merged = serde_json::Value::Null;
+    for path in paths {
+        if skip_missing && matches!(path.try_exists(), Ok(false)) {
+            continue;
+        }
+        let layer = deserialize_json_from_path(path)?;
+        merge_json(&mut merged, layer);
+    }
+
+    Ok(serde_json::from_value(merged)?)
+}
+
+/// Recursively merges `overlay` into `base`, with `overlay` taking precedence.
+fn merge_json(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                merge_json(base.entry(key).or_insert(serde_json::Value::Null), value);
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Returns all JSON (".json") files within the provided directory, sorted by filename.
+fn collect_sorted_json_files(path: impl AsRef<Path>) -> Result<Vec<PathBuf>, Error> {
+    let mut files: Vec<PathBuf> = collect_files_from_dir_path(path)?
+        .into_iter()
+        .filter(|file| file.extension() == Some(OsStr::new("json")))
+        .collect();
+    files.sort();
+
+    Ok(files)
+}
+
+/// Deserializes all JSON (".json") files in a provided directory path, in sorted filename order.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` or any of the JSON files fails.
+/// *  deserialization fails.
+pub fn deserialize_json_from_dir_path<T>(path: impl AsRef<Path>) -> Result<Vec<T>, Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    collect_sorted_json_files(path)?
+        .iter()
+        .map(deserialize_json_from_path)
+        .collect()
+}
+
+/// Deserializes all JSON (".json") files in a provided directory path concurrently, in sorted filename order.
+///
+/// Produces the same output as `deserialize_json_from_dir_path`.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` or any of the JSON files fails.
+/// *  deserialization fails.
+///
+/// When several files fail, the error from the first file in sorted order is returned.
+#[cfg(feature = "rayon")]
+pub fn deserialize_json_from_dir_path_parallel<T>(path: impl AsRef<Path>) -> Result<Vec<T>, Error>
+where
+    T: for<'a> Deserialize<'a> + Send,
+{
+    use rayon::prelude::*;
+
+    let results: Vec<Result<T, Error>> = collect_sorted_json_files(path)?
+        .par_iter()
+        .map(deserialize_json_from_path)
+        .collect();
+
+    // Collecting sequentially keeps the reported error deterministic.
+    results.into_iter().collect()
+}
+
+/// Opens a file or directory in read-only mode from provided path.
+fn open(path: impl AsRef<Path>) -> Result<(File, Metadata), Error> {
+    // Opens file from path
+    let file = File::open(&path).map_err(|source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    })?;
+
+    let metadata = file.metadata()?;
+
+    Ok((file, metadata))
+}
+
+// Opens a file in read-only mode from provided path.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// * The user lacks permissions to perform `metadata` call on `path`.
+/// * The user lacks permissions to perform `open()` call on `path`.
+/// * `path` does not exist.
+/// * `path` is not a file.
+pub fn open_file(path: impl AsRef<Path>) -> Result<File, Error> {
+    let (file, metadata) = open(&path)?;
+
+    if metadata.is_file() {
+        Ok(file)
+    } else {
+        Err(Error::InvalidType {
+            path: path.as_ref().into(),
+            msg: "attempt to open directory as a file".to_string(),
+        })
+    }
+}
+
+// Opens a directory in read-only mode from provided path.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// * The user lacks permissions to perform `metadata` call on `path`.
+/// * The user lacks permissions to perform `open()` call on `path`.
+/// * `path` does not exist.
+/// * `path` is not a directory.
+pub fn open_dir(path: impl AsRef<Path>) -> Result<File, Error> {
+    let (dir, metadata) = open(&path)?;
+
+    if metadata.is_dir() {
+        Ok(dir)
+    } else {
+        Err(Error::InvalidType {
+            path: path.as_ref().into(),
+            msg: "attempt to open file as a directory".to_string(),
+        })
+    }
+}
+
+/// Checks that the file at the provided path is valid UTF-8, before handing it to a CSV or JSON reader.
+///
+/// Invalid files (e.g. Latin-1) otherwise produce cryptic parsing errors, this points at the first invalid byte so
+/// the file can be transcoded. The file is streamed, rather than read into memory.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  reading fails.
+/// *  the file is not valid UTF-8, as `Encoding` with the offset of the first invalid byte.
+pub fn validate_utf8(path: impl AsRef<Path>) -> Result<(), Error> {
+    let mut reader = BufReader::new(open_file(&path)?);
+    let encoding_error = |byte_offset| Error::Encoding {
+        path: path.as_ref().into(),
+        byte_offset,
+    };
+
+    // Offset of the start of `pending`, which holds bytes not yet validated.
+    let mut offset = 0;
+    let mut pending = vec![];
+    let mut chunk = [0; 8192];
+    loop {
+        let n = reader.read(&mut chunk).map_err(|source| FileIoError {
+            path: path.as_ref().into(),
+            source,
+        })?;
+        if n == 0 {
+            break;
+        }
+        pending.extend_from_slice(&chunk[..n]);
+        let valid = match std::str::from_utf8(&pending) {
+            Ok(_) => pending.len(),
+            Err(e) if e.error_len().is_some() => {
+                return Err(encoding_error(offset + e.valid_up_to()));
+            }
+            // A character split across chunks, keep its leading bytes for the next chunk.
+            Err(e) => e.valid_up_to(),
+        };
+        offset += valid;
+        let _ = pending.drain(..valid);
+    }
+
+    // A truncated character at the end of the file.
+    if pending.is_empty() {
+        Ok(())
+    } else {
+        Err(encoding_error(offset))
+    }
+}
+
+/// Read the lines of a text file, with line endings stripped.
+///
+/// Any mix of `\r\n`, `\n`, and `\r` line endings is accepted, e.g. data assembled from Windows and Unix sources.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  reading fails, or the file is not valid UTF-8.
+pub fn read_lines_normalized(path: impl AsRef<Path>) -> Result<Vec<String>, Error> {
+    let mut content = String::new();
+    let _ = open_file(&path)?
+        .read_to_string(&mut content)
+        .map_err(|source| FileIoError {
+            path: path.as_ref().into(),
+            source,
+        })?;
+
+    Ok(content
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .lines()
+        .map(ToString::to_string)
+        .collect())
+}
+
+/// Deserialize CSV data from a provided path into a vector.
+///
+/// Each row of the CSV is deserialized into the user supplied `_data_type`
+/// Requires the CSV to be standard, with a header value for each field (matching the `_data_type` if it is struct).
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  serialization fails.
+pub fn deserialize_csv_rows_from_path<T>(path: impl AsRef<Path>) -> Result<Vec<T>, Error>
+where
+    T: for<'a> Deserialize<'a> + Clone,
+{
+    CsvReader::default().read_rows(path)
+}
+
+/// Deserialize CSV data from a provided path into a vector, keeping only the rows for which `pred` is true.
+///
+/// Rows are filtered as they are read, so discarded rows are never held in memory together.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  deserialization of any row fails, even one that would be discarded.
+pub fn deserialize_csv_rows_filtered_from_path<T, F>(
+    path: impl AsRef<Path>,
+    pred: F,
+) -> Result<Vec<T>, Error>
+where
+    T: for<'a> Deserialize<'a>,
+    F: Fn(&T) -> bool,
+{
+    let mut out = vec![];
+    for row in csv_struct_stream::<T>(path)? {
+        let row = row?;
+        if pred(&row) {
+            out.push(row);
+        }
+    }
+
+    Ok(out)
+}
+
+/// Reads every cell of the CSV at the provided path as a string, e.g. to inspect a messy file.
+///
+/// Nothing is assumed about the layout: rows may differ in length, the header is just the first row,
+/// and `#` lines are kept. Empty cells become empty strings.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  reading fails, or a cell is not valid UTF-8.
+pub fn read_csv_cells_from_path(path: impl AsRef<Path>) -> Result<Vec<Vec<String>>, Error> {
+    CsvReader::builder()
+        .has_headers(false)
+        .flexible(true)
+        .comment(None)
+        .build()
+        .read_rows(path)
+}
+
+/// Deserialize a single record CSV (one header row and one data row) from a provided path, e.g. a config.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  there isn't exactly one data row.
+/// *  deserialization fails.
+pub fn deserialize_csv_single_row_from_path<T>(path: impl AsRef<Path>) -> Result<T, Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    let map_err = |source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    };
+    let file = open_file(&path)?;
+    let mut reader = CsvReader::default().csv_reader(file);
+    let mut rows = reader.deserialize::<T>();
+
+    let row = rows.next().transpose().map_err(map_err)?;
+    let rest = rows.count();
+    match row {
+        Some(row) if rest == 0 => Ok(row),
+        _ => Err(Error::InvalidShape {
+            path: path.as_ref().into(),
+            msg: format!(
+                "expected exactly one data row, found {}",
+                usize::from(row.is_some()) + rest
+            ),
+        }),
+    }
+}
+
+/// Read only the header row of the CSV at the provided path, without parsing any data rows.
+///
+/// Much cheaper than reading the whole file, e.g. for schema discovery.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  reading the first record fails.
+/// *  the file is empty.
+pub fn read_csv_headers(path: impl AsRef<Path>) -> Result<Vec<String>, Error> {
+    // Open the file containing the data.
+    let file = open_file(&path)?;
+    let mut reader = CsvReader::default().csv_reader(file);
+
+    let headers = reader.headers().map_err(|source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    })?;
+    if headers.is_empty() {
+        return Err(Error::InvalidShape {
+            path: path.as_ref().into(),
+            msg: "expected a header row, found an empty file".to_string(),
+        });
+    }
+    Ok(headers.iter().map(str::to_string).collect())
+}
+
+/// Lazily deserialize CSV data from a provided path, yielding one `T` per row.
+///
+/// Unlike `deserialize_csv_rows_from_path`, rows are parsed on demand so memory use stays flat.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+///
+/// Each item is an error if deserialization of that row fails.
+pub fn csv_struct_stream<T>(
+    path: impl AsRef<Path>,
+) -> Result<impl Iterator<Item = Result<T, Error>>, Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    // Open the file containing the data.
+    let file = open_file(&path)?;
+    let path: Box<Path> = path.as_ref().into();
+
+    Ok(CsvReader::default()
+        .csv_reader(file)
+        .into_deserialize::<T>()
+        .map(move |result| {
+            result.map_err(|source| {
+                FileIoError {
+                    path: path.clone(),
+                    source,
+                }
+                .into()
+            })
+        }))
+}
+
+/// Lazily deserialize CSV data from a provided path, yielding batches of up to `batch_size` rows.
+///
+/// Every batch is full except possibly the last, e.g. for inserting rows into a database in fixed-size transactions.
+/// Only one batch is held in memory at a time.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  `batch_size` is zero.
+///
+/// Each item is an error if deserialization of a row in that batch fails, the rest of that batch is discarded.
+pub fn csv_row_batches_from_path<T>(
+    path: impl AsRef<Path>,
+    batch_size: usize,
+) -> Result<impl Iterator<Item = Result<Vec<T>, Error>>, Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    if batch_size == 0 {
+        return Err(Error::InvalidValue {
+            path: path.as_ref().into(),
+            msg: "batch size must be at least 1".to_string(),
+        });
+    }
+    let mut rows = csv_struct_stream::<T>(path)?;
+
+    Ok(std::iter::from_fn(move || {
+        let mut batch = Vec::with_capacity(batch_size.min(1024));
+        for row in rows.by_ref() {
+            match row {
+                Ok(row) => batch.push(row),
+                Err(e) => return Some(Err(e)),
+            }
+            if batch.len() == batch_size {
+                break;
+            }
+        }
+        (!batch.is_empty()).then_some(Ok(batch))
+    }))
+}
+
+/// Deserialize CSV data from a provided path into a vector, ignoring the last `skip_trailing` rows.
+///
+/// Intended for exports with trailing summary rows (e.g. `Total,...`), which are dropped before being parsed.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  deserialization fails.
+pub fn deserialize_csv_rows_skip_trailing_from_path<T>(
+    path: impl AsRef<Path>,
+    skip_trailing: usize,
+) -> Result<Vec<T>, Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    CsvReader::builder()
+        .skip_trailing(skip_trailing)
+        .build()
+        .read_rows(path)
+}
+
+/// Deserialize CSV data from a provided path into a vector, pairing each row with its 1-based line number in the file.
+///
+/// Line numbers account for the header and any comment lines, so they can be used to point at the source of a row.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening or reading `path` fails.
+/// *  deserialization fails.
+pub fn deserialize_csv_rows_with_positions_from_path<T>(
+    path: impl AsRef<Path>,
+) -> Result<Vec<(u64, T)>, Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    let map_err = |source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    };
+    // Open the file containing the data.
+    let file = open_file(&path)?;
+    let mut reader = CsvReader::default().csv_reader(file);
+    let headers = reader.headers().map_err(map_err)?.clone();
+
+    // A second handle on the file, kept in step with the reader to count the comment and blank lines it skips.
+    let mut scan = BufReader::new(open_file(&path)?);
+    let mut scanned = 0;
+
+    let mut out = vec![];
+    for record in reader.records() {
+        let record = record.map_err(map_err)?;
+        let position = record
+            .position()
+            .cloned()
+            .unwrap_or_else(csv::Position::new);
+        // The reported position is the end of the previous record, before any comment or blank lines that follow it.
+        let skipped =
+            skip_csv_comment_lines(&mut scan, &mut scanned, position.byte()).map_err(|source| {
+                FileIoError {
+                    path: path.as_ref().into(),
+                    source,
+                }
+            })?;
+        out.push((
+            position.line() + skipped,
+            record.deserialize(Some(&headers)).map_err(map_err)?,
+        ));
+    }
+
+    Ok(out)
+}
+
+/// Move `scan` forward from byte `scanned` to `target`, then past any comment or blank lines, returning the number of lines skipped.
+fn skip_csv_comment_lines<R: BufRead + Seek>(
+    scan: &mut R,
+    scanned: &mut u64,
+    target: u64,
+) -> std::io::Result<u64> {
+    let offset = target
+        .checked_sub(*scanned)
+        .and_then(|offset| i64::try_from(offset).ok())
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "expected a record after byte {}, found one at byte {target}",
+                    *scanned
+                ),
+            )
+        })?;
+    scan.seek_relative(offset)?;
+    *scanned = target;
+
+    let mut skipped = 0;
+    let mut comment = false;
+    while let Some(&byte) = scan.fill_buf()?.first() {
+        match byte {
+            b'\n' => {
+                skipped += 1;
+                comment = false;
+            }
+            b'#' | b'\r' => comment |= byte == b'#',
+            _ if comment => {}
+            _ => break,
+        }
+        scan.consume(1);
+        *scanned += 1;
+    }
+    Ok(skipped)
+}
+
+/// Deserialize CSV data from a provided path into a vector, after checking the header against `expected_headers`.
+///
+/// With `check_order` the header must match exactly, otherwise the same set of names in any order is accepted.
+/// The header is checked before any rows are parsed.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  the header doesn't match `expected_headers`.
+/// *  deserialization fails.
+pub fn deserialize_csv_rows_checked_from_path<T>(
+    path: impl AsRef<Path>,
+    expected_headers: &[&str],
+    check_order: bool,
+) -> Result<Vec<T>, Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    let map_err = |source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    };
+    let file = open_file(&path)?;
+    let mut reader = CsvReader::default().csv_reader(file);
+    let found: Vec<&str> = reader.headers().map_err(map_err)?.iter().collect();
+
+    let matches = if check_order {
+        found == expected_headers
+    } else {
+        let mut found = found.clone();
+        let mut expected = expected_headers.to_vec();
+        found.sort_unstable();
+        expected.sort_unstable();
+        found == expected
+    };
+    if !matches {
+        return Err(Error::HeaderMismatch {
+            path: path.as_ref().into(),
+            expected: expected_headers.iter().map(ToString::to_string).collect(),
+            found: found.iter().map(ToString::to_string).collect(),
+        });
+    }
+
+    reader
+        .deserialize::<T>()
+        .map(|result| Ok(result.map_err(map_err)?))
+        .collect()
+}
+
+/// Deserialize CSV data with several header rows (e.g. names followed by units) from a provided path.
+///
+/// The first `header_rows` rows are returned as raw strings, and the remaining rows are deserialized into `T`.
+/// The first header row provides the field names when deserializing into a struct.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  deserialization fails.
+pub fn deserialize_csv_multiheader_from_path<T>(
+    path: impl AsRef<Path>,
+    header_rows: usize,
+) -> Result<(Vec<Vec<String>>, Vec<T>), Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    let map_err = |source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    };
+    let file = open_file(&path)?;
+    // Headers are handled here rather than by the reader.
+    let mut reader = CsvReader::builder()
+        .has_headers(false)
+        .build()
+        .csv_reader(file);
+
+    let mut headers = vec![];
+    let mut out = vec![];
+    for record in reader.records() {
+        let record = record.map_err(map_err)?;
+        if headers.len() < header_rows {
+            headers.push(record);
+        } else {
+            out.push(record.deserialize(headers.first()).map_err(map_err)?);
+        }
+    }
+    let headers = headers
+        .iter()
+        .map(|record| record.iter().map(ToString::to_string).collect())
+        .collect();
+
+    Ok((headers, out))
+}
+
+/// Deserialize CSV data from a provided path into a vector, renaming header fields before deserialization.
+///
+/// Each header found in `rename` is replaced by its mapped name (e.g. `"Air Temperature"` to `"air_temperature"`),
+/// so external schemas can be matched to struct fields at runtime. Unmapped headers pass through unchanged.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  deserialization fails.
+pub fn deserialize_csv_rows_renamed_from_path<T, S>(
+    path: impl AsRef<Path>,
+    rename: &HashMap<String, String, S>,
+) -> Result<Vec<T>, Error>
+where
+    T: for<'a> Deserialize<'a>,
+    S: BuildHasher,
+{
+    let map_err = |source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    };
+    let file = open_file(&path)?;
+    let mut reader = CsvReader::default().csv_reader(file);
+    let headers: csv::StringRecord = reader
+        .headers()
+        .map_err(map_err)?
+        .iter()
+        .map(|header| rename.get(header).map_or(header, String::as_str))
+        .collect();
+    reader.set_headers(headers);
+
+    reader
+        .deserialize::<T>()
+        .map(|result| Ok(result.map_err(map_err)?))
+        .collect()
+}
+
+/// Deserialize CSV data with grouped numbers (e.g. `1,234.56`) from a provided path into a vector.
+///
+/// Fields are separated by `delimiter`, which must differ from the thousands `separator` unless fields are quoted.
+/// The `separator` is stripped only from fields that are numbers grouped in threes, other fields (e.g. labels) are untouched.
+/// As the target type isn't known at that point, a string field holding such a number, e.g. `"1,000"`, is stripped too.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  deserialization fails.
+pub fn deserialize_csv_numeric_with_grouping_from_path<T>(
+    path: impl AsRef<Path>,
+    delimiter: u8,
+    separator: char,
+) -> Result<Vec<T>, Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    let map_err = |source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    };
+    let file = open_file(&path)?;
+    let mut reader = CsvReader::builder()
+        .delimiter(delimiter)
+        .build()
+        .csv_reader(file);
+    let headers = reader.headers().map_err(map_err)?.clone();
+
+    reader
+        .records()
+        .map(|record| {
+            let record: csv::StringRecord = record
+                .map_err(map_err)?
+                .iter()
+                .map(|field| strip_grouping(field, separator).unwrap_or_else(|| field.to_string()))
+                .collect();
+            Ok(record.deserialize(Some(&headers)).map_err(map_err)?)
+        })
+        .collect()
+}
+
+/// Remove the `separator` from a number grouped in threes, e.g. `1,234.56`, or `None` if `field` is not one.
+fn strip_grouping(field: &str, separator: char) -> Option<String> {
+    let unsigned = field.trim().trim_start_matches(['+', '-']);
+    let integer_len = unsigned
+        .find(|c: char| !c.is_ascii_digit() && c != separator)
+        .unwrap_or(unsigned.len());
+    let mut groups = unsigned[..integer_len].split(separator);
+    let first = groups.next()?.len();
+    let mut rest = groups.peekable();
+    if rest.peek().is_none() || !(1..=3).contains(&first) || rest.any(|group| group.len() != 3) {
+        return None;
+    }
+
+    let stripped = field.replace(separator, "");
+    stripped.trim().parse::<f64>().is_ok().then_some(stripped)
+}
+
+/// A struct of column vectors, populated from the CSV columns with matching headers.
+///
+/// Usually implemented with the `csv_columns!` macro rather than by hand.
+pub trait CsvColumns: Default {
+    /// One row, as a tuple of the column types in the order of `HEADERS`.
+    type Row: for<'a> Deserialize<'a>;
+    /// Headers of the columns, in the order of the fields.
+    const HEADERS: &'static [&'static str];
+
+    /// Append one row to the columns.
+    fn push_row(&mut self, row: Self::Row);
+}
+
+/// Declare a struct of column vectors implementing `CsvColumns`, with one field per CSV header.
+///
+/// Each field must be a `Vec<T>`, and is populated from the column whose header matches the field name.
+/// Rows are read as tuples, so a struct may have at most 16 fields, serde's limit for deserializing a tuple.
+/// More fields fail to compile with an unsatisfied `Deserialize` bound on the row tuple.
+/// ```
+/// sci_file::csv_columns! {
+///     #[derive(Debug)]
+///     pub struct Table {
+///         pub id: Vec<u32>,
+///         pub value: Vec<f64>,
+///         pub label: Vec<String>,
+///     }
+/// }
+/// let table: Table = sci_file::deserialize_csv_into_struct_of_vecs("tests/data/mixed_types.csv").unwrap();
+/// assert_eq!(vec![1, 2, 3], table.id);
+/// ```
+#[macro_export]
+macro_rules! csv_columns {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($field_vis:vis $field:ident : Vec<$ty:ty>),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Default)]
+        $vis struct $name {
+            $($field_vis $field: Vec<$ty>),*
+        }
+
+        impl $crate::CsvColumns for $name {
+            type Row = ($($ty,)*);
+            const HEADERS: &'static [&'static str] = &[$(stringify!($field)),*];
+
+            fn push_row(&mut self, row: Self::Row) {
+                let ($($field,)*) = row;
+                $(self.$field.push($field);)*
+            }
+        }
+    };
+}
+
+/// Deserialize CSV data from a provided path into a struct of column vectors (see `csv_columns!`).
+///
+/// Each column keeps its own type, so e.g. integer, float, and string columns are read in one pass.
+/// Columns without a matching field are ignored.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  a field has no matching header.
+/// *  deserialization fails.
+pub fn deserialize_csv_into_struct_of_vecs<T: CsvColumns>(
+    path: impl AsRef<Path>,
+) -> Result<T, Error> {
+    let map_err = |source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    };
+    let file = open_file(&path)?;
+    let mut reader = CsvReader::default().csv_reader(file);
+    let headers = reader.headers().map_err(map_err)?.clone();
+    let indices = T::HEADERS
+        .iter()
+        .map(|field| headers.iter().position(|header| header == *field))
+        .collect::<Option<Vec<usize>>>()
+        .ok_or_else(|| Error::HeaderMismatch {
+            path: path.as_ref().into(),
+            expected: T::HEADERS.iter().map(ToString::to_string).collect(),
+            found: headers.iter().map(ToString::to_string).collect(),
+        })?;
+
+    let mut columns = T::default();
+    for record in reader.records() {
+        let record = record.map_err(map_err)?;
+        let selected: csv::StringRecord = indices.iter().map(|&i| &record[i]).collect();
+        columns.push_row(selected.deserialize(None).map_err(map_err)?);
+    }
+
+    Ok(columns)
+}
+
+/// Detects the delimiter of the CSV at the provided path, from `,`, tab, `;`, and `|`.
+///
+/// The first few non-comment lines are split with each candidate, and the candidate producing the same number of
+/// fields (more than one) on every line wins. If several qualify, the one producing the most fields is chosen.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening or reading `path` fails.
+/// *  the file is empty, or no candidate (or more than one, equally) is consistent.
+pub fn detect_csv_delimiter(path: impl AsRef<Path>) -> Result<u8, Error> {
+    const CANDIDATES: [u8; 4] = [b',', b'\t', b';', b'|'];
+    const SAMPLE_LINES: usize = 10;
+
+    let mut sample = String::new();
+    let mut sampled = 0;
+    for line in BufReader::new(open_file(&path)?).lines() {
+        let line = line.map_err(|source| FileIoError {
+            path: path.as_ref().into(),
+            source,
+        })?;
+        if !line.trim().is_empty() && !line.starts_with('#') {
+            sample.push_str(&line);
+            sample.push('\n');
+            sampled += 1;
+            if sampled == SAMPLE_LINES {
+                break;
+            }
+        }
+    }
+
+    // Field count of every sampled line, if consistent, for each candidate.
+    let mut consistent: Vec<(u8, usize)> = vec![];
+    for delimiter in CANDIDATES {
+        let mut reader = CsvReader::builder()
+            .delimiter(delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .build()
+            .csv_reader(sample.as_bytes());
+        let counts: Vec<usize> = reader
+            .records()
+            .map_while(Result::ok)
+            .map(|r| r.len())
+            .collect();
+        if let Some(&first) = counts.first()
+            && first > 1
+            && counts.iter().all(|&count| count == first)
+        {
+            consistent.push((delimiter, first));
+        }
+    }
+    consistent.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+
+    match consistent.as_slice() {
+        [(delimiter, _)] => Ok(*delimiter),
+        [(delimiter, most), (_, next), ..] if most > next => Ok(*delimiter),
+        _ => Err(Error::InvalidValue {
+            path: path.as_ref().into(),
+            msg: "unable to detect an unambiguous CSV delimiter".to_string(),
+        }),
+    }
+}
+
+/// Deserialize CSV data from a provided path into a vector, detecting the delimiter with `detect_csv_delimiter`.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  the delimiter cannot be detected.
+/// *  deserialization fails.
+pub fn deserialize_csv_rows_auto_from_path<T>(path: impl AsRef<Path>) -> Result<Vec<T>, Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    CsvReader::builder()
+        .delimiter(detect_csv_delimiter(&path)?)
+        .build()
+        .read_rows(path)
+}
+
+/// A labelled table, as read by `deserialize_csv_indexed_from_path`.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default, Clone)]
+pub struct IndexedRows<I, T> {
+    /// The header of the index column, if requested.
+    pub index_header: Option<String>,
+    /// The index (column 0) of each row.
+    pub index: Vec<I>,
+    /// The remaining columns of each row.
+    pub rows: Vec<Vec<T>>,
+}
+
+/// Deserialize a labelled table from a provided path, with column 0 parsed as the row index `I` and the remaining
+/// columns as `T`, e.g. timestamps or station IDs beside numeric data.
+///
+/// The header of column 0 is kept if `keep_index_header` is set, and is otherwise `None`.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  deserialization of the index or any value fails.
+pub fn deserialize_csv_indexed_from_path<I, T>(
+    path: impl AsRef<Path>,
+    keep_index_header: bool,
+) -> Result<IndexedRows<I, T>, Error>
+where
+    I: for<'a> Deserialize<'a>,
+    T: for<'a> Deserialize<'a>,
+{
+    let map_err = |source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    };
+    // Open the file containing the data.
+    let file = open_file(&path)?;
+    let mut reader = CsvReader::default().csv_reader(file);
+
+    let index_header = if keep_index_header {
+        reader
+            .headers()
+            .map_err(map_err)?
+            .get(0)
+            .map(str::to_string)
+    } else {
+        None
+    };
+
+    let mut index = vec![];
+    let mut rows = vec![];
+    for record in reader.records() {
+        let record = record.map_err(map_err)?;
+        let mut fields = record.iter();
+        let label: csv::StringRecord = fields.next().into_iter().collect();
+        index.push(label.deserialize(None).map_err(map_err)?);
+        let values: csv::StringRecord = fields.collect();
+        rows.push(values.deserialize(None).map_err(map_err)?);
+    }
+
+    Ok(IndexedRows {
+        index_header,
+        index,
+        rows,
+    })
+}
+
+/// Deserialize CSV data from a provided path into a vector, after capturing `preamble_lines` lines of metadata.
+///
+/// Instrument files often begin with lines such as `Instrument: X` before the table, which would otherwise be lost
+/// (or fail to parse). The preamble lines are returned raw, with line endings stripped, and the next line is the
+/// CSV header.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening or reading `path` fails.
+/// *  the file has fewer than `preamble_lines` lines.
+/// *  deserialization fails.
+pub fn deserialize_csv_with_preamble_from_path<T>(
+    path: impl AsRef<Path>,
+    preamble_lines: usize,
+) -> Result<(Vec<String>, Vec<T>), Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    let mut reader = BufReader::new(open_file(&path)?);
+
+    let mut preamble = Vec::new();
+    for _ in 0..preamble_lines {
+        let mut line = String::new();
+        let n = reader.read_line(&mut line).map_err(|source| FileIoError {
+            path: path.as_ref().into(),
+            source,
+        })?;
+        if n == 0 {
+            return Err(Error::InvalidShape {
+                path: path.as_ref().into(),
+                msg: format!(
+                    "expected {preamble_lines} preamble lines, found {}",
+                    preamble.len()
+                ),
+            });
+        }
+        let trimmed = line.trim_end_matches(['\n', '\r']).len();
+        line.truncate(trimmed);
+        preamble.push(line);
+    }
+
+    // The remainder of the file is the table.
+    let rows = CsvReader::default().read_csv_rows(reader, path.as_ref())?;
+
+    Ok((preamble, rows))
+}
+
+/// Deserialize CSV data from a provided path into a vector, treating any byte in `delimiters` as a field separator.
+///
+/// A lenient reader for inconsistently delimited files, e.g. a bad export mixing tabs and commas, which the strict
+/// reader rejects. Every delimiter outside quoted fields is normalized to the first of `delimiters` before parsing.
+/// If `collapse` is set, consecutive delimiters are treated as one, e.g. for columns aligned with mixed whitespace.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening or reading `path` fails.
+/// *  `delimiters` is empty.
+/// *  deserialization fails.
+pub fn deserialize_csv_normalizing_delimiters_from_path<T>(
+    path: impl AsRef<Path>,
+    delimiters: &[u8],
+    collapse: bool,
+) -> Result<Vec<T>, Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    let Some(&delimiter) = delimiters.first() else {
+        return Err(Error::InvalidValue {
+            path: path.as_ref().into(),
+            msg: "expected at least one delimiter".to_string(),
+        });
+    };
+    let mut data = vec![];
+    let _ = open_file(&path)?
+        .read_to_end(&mut data)
+        .map_err(|source| FileIoError {
+            path: path.as_ref().into(),
+            source,
+        })?;
+
+    let mut normalized = Vec::with_capacity(data.len());
+    let mut in_quotes = false;
+    for byte in data {
+        if byte == b'"' {
+            in_quotes = !in_quotes;
+        } else if !in_quotes && delimiters.contains(&byte) {
+            if collapse && normalized.last() == Some(&delimiter) {
+                continue;
+            }
+            normalized.push(delimiter);
+            continue;
+        }
+        normalized.push(byte);
+    }
+
+    CsvReader::builder()
+        .delimiter(delimiter)
+        .build()
+        .read_csv_rows(&normalized[..], path.as_ref())
+}
+
+/// Deserialize a single CSV record into `T`, matching fields to `headers`.
+///
+/// The building block for custom loops driving a `csv::Reader` directly, with the crate's error type.
+/// # Errors
+///
+/// Returns an error if deserialization fails.
+pub fn deserialize_csv_record<T>(
+    record: &csv::StringRecord,
+    headers: &csv::StringRecord,
+) -> Result<T, Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    Ok(record.deserialize(Some(headers))?)
+}
+
+/// Deserialize CSV data from any reader into a vector.
+///
+/// Identical to `deserialize_csv_rows_from_path`, but parses data that doesn't live on the filesystem
+/// (e.g. a socket, or an in-memory `&[u8]`).
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  reading fails.
+/// *  serialization fails.
+pub fn deserialize_csv_rows_from_reader<R, T>(reader: R) -> Result<Vec<T>, Error>
+where
+    R: Read,
+    T: for<'a> Deserialize<'a> + Clone,
+{
+    CsvReader::default().read_rows_from_reader(reader)
+}
+
+/// Configuration for a `CsvReader`, starting from the standard settings used throughout the crate.
+///
+/// e.g. `CsvReader::builder().delimiter(b';').comment(None).has_headers(false).build().read_rows::<T>(path)`
+#[derive(Debug, Clone)]
+pub struct CsvReaderBuilder {
+    delimiter: u8,
+    quote: u8,
+    comment: Option<u8>,
+    has_headers: bool,
+    flexible: bool,
+    trim: bool,
+    null_tokens: Vec<String>,
+    skip_trailing: usize,
+}
+
+impl Default for CsvReaderBuilder {
+    // The standard settings used throughout the crate.
+    fn default() -> Self {
+        Self {
+            delimiter: b',',     // Entries are comma separated (actual CSV).
+            quote: b'"',         // Fields containing the delimiter are quoted.
+            comment: Some(b'#'), // Comment lines start with '#'.
+            has_headers: true,   // CSV header is expected.
+            flexible: false,     // All rows must have the same number of fields.
+            trim: false,         // Fields are read as written.
+            null_tokens: vec![],
+            skip_trailing: 0,
+        }
+    }
+}
+
+impl CsvReaderBuilder {
+    /// Creates a builder with the standard settings used throughout the crate.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Field delimiter, `,` by default.
+    #[must_use]
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Quote character, around fields containing the delimiter, `"` by default.
+    #[must_use]
+    pub fn quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    /// Comment line prefix, `#` by default, `None` disables comments.
+    #[must_use]
+    pub fn comment(mut self, comment: Option<u8>) -> Self {
+        self.comment = comment;
+        self
+    }
+
+    /// Whether the first row is a header, `true` by default.
+    #[must_use]
+    pub fn has_headers(mut self, has_headers: bool) -> Self {
+        self.has_headers = has_headers;
+        self
+    }
+
+    /// Whether rows may have differing numbers of fields, `false` by default.
+    #[must_use]
+    pub fn flexible(mut self, flexible: bool) -> Self {
+        self.flexible = flexible;
+        self
+    }
+
+    /// Whether whitespace around fields and headers is trimmed, `false` by default.
+    #[must_use]
+    pub fn trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Cells matching any of these tokens (ignoring ASCII case and surrounding whitespace) are read as `NaN`.
+    ///
+    /// Other cells are parsed normally, and any that fail are reported as `InvalidValue` with their row and column.
+    #[must_use]
+    pub fn null_tokens(mut self, null_tokens: &[&str]) -> Self {
+        self.null_tokens = null_tokens.iter().map(ToString::to_string).collect();
+        self
+    }
+
+    /// Number of trailing rows to ignore (e.g. summary rows), `0` by default.
+    #[must_use]
+    pub fn skip_trailing(mut self, skip_trailing: usize) -> Self {
+        self.skip_trailing = skip_trailing;
+        self
+    }
+
+    /// Creates a `CsvReader` with these settings.
+    #[must_use]
+    pub fn build(self) -> CsvReader {
+        CsvReader { options: self }
+    }
+
+    /// The `csv` reader settings, other than those applied while reading records.
+    fn reader_builder(&self) -> ReaderBuilder {
+        let mut builder = ReaderBuilder::new();
+        let _ = builder
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .comment(self.comment)
+            .has_headers(self.has_headers)
+            .flexible(self.flexible)
+            .trim(if self.trim {
+                csv::Trim::All
+            } else {
+                csv::Trim::None
+            });
+        builder
+    }
+}
+
+/// Reads CSV data with the settings of a `CsvReaderBuilder`.
+///
+/// The `deserialize_csv_*` functions are presets of this reader, or walk the records of its `csv::Reader`.
+#[derive(Debug, Clone, Default)]
+pub struct CsvReader {
+    options: CsvReaderBuilder,
+}
+
+impl CsvReader {
+    /// Creates a `CsvReaderBuilder`, starting from the standard settings.
+    #[must_use]
+    pub fn builder() -> CsvReaderBuilder {
+        CsvReaderBuilder::new()
+    }
+
+    /// Deserialize CSV data from a provided path into a vector, one `T` per row.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  opening `path` fails.
+    /// *  deserialization fails.
+    pub fn read_rows<T>(&self, path: impl AsRef<Path>) -> Result<Vec<T>, Error>
+    where
+        T: for<'a> Deserialize<'a>,
+    {
+        let file = open_file(&path)?;
+        self.read_csv_rows(file, path.as_ref())
+    }
+
+    /// Deserialize CSV data from any reader into a vector, one `T` per row.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  reading fails.
+    /// *  deserialization fails.
+    pub fn read_rows_from_reader<R, T>(&self, reader: R) -> Result<Vec<T>, Error>
+    where
+        R: Read,
+        T: for<'a> Deserialize<'a>,
+    {
+        self.read_csv_rows(reader, Path::new("<reader>"))
+    }
+
+    /// A `csv::Reader` with these settings, for reading records directly.
+    ///
+    /// Null tokens and trailing rows are only handled by `read_rows`.
+    pub(crate) fn csv_reader<R: Read>(&self, reader: R) -> csv::Reader<R> {
+        self.options.reader_builder().from_reader(reader)
+    }
+
+    /// Deserializes every row of the CSV data, with `path` for error context.
+    fn read_csv_rows<R, T>(&self, reader: R, path: &Path) -> Result<Vec<T>, Error>
+    where
+        R: Read,
+        T: for<'a> Deserialize<'a>,
+    {
+        let map_err = |source| FileIoError {
+            path: path.into(),
+            source,
+        };
+        let options = &self.options;
+        let mut reader = self.csv_reader(reader);
+        let headers = if options.has_headers {
+            Some(reader.byte_headers().map_err(map_err)?.clone())
+        } else {
+            None
+        };
+
+        // Rows are only parsed once they can no longer be among the trailing rows.
+        let mut pending =
+            VecDeque::with_capacity(options.skip_trailing.saturating_add(1).min(1024));
+        let mut out = vec![];
+        for (row, record) in reader.byte_records().enumerate() {
+            let mut record = record.map_err(map_err)?;
+            if !options.null_tokens.is_empty() {
+                let position = record.position().cloned();
+                record = record
+                    .iter()
+                    .map(|cell| {
+                        let cell_trimmed = cell.trim_ascii();
+                        let is_null = options
+                            .null_tokens
+                            .iter()
+                            .any(|token| token.as_bytes().eq_ignore_ascii_case(cell_trimmed));
+                        if is_null { b"NaN".as_slice() } else { cell }
+                    })
+                    .collect();
+                record.set_position(position);
+            }
+            pending.push_back((row, record));
+            if pending.len() > options.skip_trailing
+                && let Some((row, record)) = pending.pop_front()
+            {
+                let value = record.deserialize(headers.as_ref()).map_err(|source| {
+                    match (source.kind(), options.null_tokens.is_empty()) {
+                        (csv::ErrorKind::Deserialize { err, .. }, false) => {
+                            let column = err.field().unwrap_or_default();
+                            let cell = usize::try_from(column)
+                                .ok()
+                                .and_then(|column| record.get(column))
+                                .unwrap_or_default();
+                            Error::InvalidValue {
+                                path: path.into(),
+                                msg: format!(
+                                    "expected a value or null token at row {row} column {column}, found `{}`: {}",
+                                    String::from_utf8_lossy(cell),
+                                    err.kind()
+                                ),
+                            }
+                        }
+                        _ => map_err(source).into(),
+                    }
+                })?;
+                out.push(value);
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+/// Deserialize numeric CSV data from a provided path into a vector of rows, substituting `missing` for empty cells.
+///
+/// Typically `missing` is `f64::NAN`. Non-empty cells are parsed normally, so malformed values still error.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  deserialization of a non-empty cell fails.
+pub fn deserialize_csv_rows_with_missing_from_path(
+    path: impl AsRef<Path>,
+    missing: f64,
+) -> Result<Vec<Vec<f64>>, Error> {
+    // Empty fields deserialize to `None`.
+    let rows = deserialize_csv_rows_from_path::<Vec<Option<f64>>>(path)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| row.into_iter().map(|v| v.unwrap_or(missing)).collect())
+        .collect())
+}
+
+/// Deserialize numeric CSV data from a provided path into a vector of rows, mapping `null_tokens` to `NaN`.
+///
+/// Cells matching a token (ignoring ASCII case and surrounding whitespace), e.g. `NA` or a `-9999` sentinel,
+/// become `f64::NAN`. Other cells are parsed normally, so malformed values still error.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  a cell is neither a null token nor a number.
+pub fn deserialize_csv_with_null_tokens_from_path(
+    path: impl AsRef<Path>,
+    null_tokens: &[&str],
+) -> Result<Vec<Vec<f64>>, Error> {
+    CsvReader::builder()
+        .trim(true)
+        .null_tokens(null_tokens)
+        .build()
+        .read_rows(path)
+}
+
+/// Compares two numeric CSV files element-wise, e.g. generated output against a golden file.
+///
+/// Returns false if the shapes differ, or any pair of values differs by more than `tolerance`.
+/// Headers are not compared, and `NaN` only matches `NaN`.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening either path fails.
+/// *  deserialization fails.
+pub fn csv_files_approx_equal(a: &Path, b: &Path, tolerance: f64) -> Result<bool, Error> {
+    let a = deserialize_csv_rows_from_path::<Vec<f64>>(a)?;
+    let b = deserialize_csv_rows_from_path::<Vec<f64>>(b)?;
+    if a.len() != b.len() {
+        return Ok(false);
+    }
+
+    Ok(a.iter().zip(&b).all(|(row_a, row_b)| {
+        row_a.len() == row_b.len()
+            && row_a
+                .iter()
+                .zip(row_b)
+                .all(|(x, y)| (x.is_nan() && y.is_nan()) || (x - y).abs() <= tolerance)
+    }))
+}
+
+/// How rows sharing a key are collapsed by `deserialize_csv_deduped_by_column_from_path`.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Aggregation {
+    /// Keep the first row for each key.
+    First,
+    /// Keep the last row for each key.
+    Last,
+    /// Average every column over the rows for each key.
+    Mean,
+}
+
+/// Deserialize numeric CSV data from a provided path into rows with unique values in column `key_col`.
+///
+/// Rows sharing a key are collapsed according to `agg`, e.g. duplicate timestamps in a sensor log, so the result
+/// is safe to build an interpolator from. Rows are kept in order of the first appearance of their key.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  `key_col` is not a column of the CSV.
+/// *  deserialization fails.
+pub fn deserialize_csv_deduped_by_column_from_path(
+    path: impl AsRef<Path>,
+    key_col: usize,
+    agg: Aggregation,
+) -> Result<Vec<Vec<f64>>, Error> {
+    let rows = deserialize_csv_rows_from_path::<Vec<f64>>(&path)?;
+
+    // Index into `out` for each key, `-0.` and `0.` are the same key.
+    let mut index: HashMap<u64, usize> = HashMap::new();
+    let mut out: Vec<Vec<f64>> = vec![];
+    let mut counts: Vec<u32> = vec![];
+    for row in rows {
+        let Some(&key) = row.get(key_col) else {
+            return Err(Error::InvalidShape {
+                path: path.as_ref().into(),
+                msg: format!(
+                    "key column {key_col} out of range for {} columns",
+                    row.len()
+                ),
+            });
+        };
+        match index.get(&(key + 0.).to_bits()) {
+            None => {
+                let _ = index.insert((key + 0.).to_bits(), out.len());
+                out.push(row);
+                counts.push(1);
+            }
+            Some(&i) => match agg {
+                Aggregation::First => {}
+                Aggregation::Last => out[i] = row,
+                Aggregation::Mean => {
+                    out[i].iter_mut().zip(row).for_each(|(sum, v)| *sum += v);
+                    counts[i] += 1;
+                }
+            },
+        }
+    }
+
+    if agg == Aggregation::Mean {
+        for (row, count) in out.iter_mut().zip(counts) {
+            for v in row {
+                *v /= f64::from(count);
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Uniformly samples up to `k` rows from the CSV at the provided path, in a single streaming pass.
+///
+/// Uses reservoir sampling with an RNG seeded by `seed`, so the sample is reproducible.
+/// If the file has no more than `k` rows, all rows are returned in file order.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  deserialization fails.
+pub fn sample_csv_rows_from_path<T>(
+    path: impl AsRef<Path>,
+    k: usize,
+    seed: u64,
+) -> Result<Vec<T>, Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    let file = open_file(&path)?;
+    let mut reader = CsvReader::default().csv_reader(file);
+    let mut rng = SplitMix64(seed);
+    let mut reservoir = Vec::with_capacity(k.min(1024));
+
+    for (i, result) in reader.deserialize::<T>().enumerate() {
+        let row: T = result.map_err(|source| FileIoError {
+            path: path.as_ref().into(),
+            source,
+        })?;
+        if i < k {
+            reservoir.push(row);
+        } else {
+            // Replace a reservoir entry with probability k / (i + 1).
+            let j = rng.below(i + 1);
+            if j < k {
+                reservoir[j] = row;
+            }
+        }
+    }
+
+    Ok(reservoir)
+}
+
+/// Minimal seeded pseudo-random number generator (`SplitMix64`), for reproducible sampling.
+#[derive(Debug)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value in `0..n`.
+    fn below(&mut self, n: usize) -> usize {
+        // Multiply-shift maps the full `u64` range onto `0..n`, the result always fits in `usize`.
+        let scaled = (u128::from(self.next_u64()) * n as u128) >> 64;
+        usize::try_from(scaled).unwrap_or(n - 1)
+    }
+}
+
+/// Deserialize CSV data from a provided path, computing the quantiles `qs` of each column.
+///
+/// Returns one value per requested quantile (e.g. `[0.5, 0.95]` for median and p95) for each column.
+/// Unlike the stats in `deserialize_csv_columns_with_stats_from_path`, this needs the full columns in memory.
+/// Quantiles are linearly interpolated between the sorted values, and `NaN` values are excluded.
+/// A quantile outside `[0, 1]`, or of a column without any non-`NaN` values, is `NaN`.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  deserialization fails.
+pub fn deserialize_csv_columns_with_quantiles_from_path(
+    path: impl AsRef<Path>,
+    qs: &[f64],
+) -> Result<Vec<Vec<f64>>, Error> {
+    let columns = deserialize_csv_column_vectors_from_path::<f64>(path)?;
+
+    Ok(columns
+        .iter()
+        .map(|column| {
+            let mut sorted: Vec<f64> = column.iter().copied().filter(|v| !v.is_nan()).collect();
+            sorted.sort_by(f64::total_cmp);
+            qs.iter().map(|&q| quantile(&sorted, q)).collect()
+        })
+        .collect())
+}
+
+/// Linearly interpolated quantile `q` of sorted, non-`NaN` values.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() || !(0. ..=1.).contains(&q) {
+        return f64::NAN;
+    }
+    // Fractional index of the quantile, within `0..=len - 1`.
+    let h = (sorted.len() - 1) as f64 * q;
+    let lo = h.floor() as usize;
+    let hi = h.ceil() as usize;
+
+    sorted[lo] + (h - h.floor()) * (sorted[hi] - sorted[lo])
+}
+
+/// Type of a CSV column, as inferred by `infer_csv_schema`.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum InferredType {
+    Integer,
+    Float,
+    Boolean,
+    Text,
+}
+
+impl InferredType {
+    /// Infers the narrowest type a single non-empty cell parses as.
+    fn of_cell(cell: &str) -> Self {
+        if cell.parse::<i64>().is_ok() {
+            Self::Integer
+        } else if cell.parse::<f64>().is_ok() {
+            Self::Float
+        } else if cell.parse::<bool>().is_ok() {
+            Self::Boolean
+        } else {
+            Self::Text
+        }
+    }
+
+    /// Widens two inferred types to one that can represent both.
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Self::Integer | Self::Float, Self::Integer | Self::Float) => Self::Float,
+            _ => Self::Text,
+        }
+    }
+}
+
+/// Infers the type of each column from the first `sample_rows` data rows of the CSV at the provided path.
+///
+/// Each sampled cell is parsed as an integer, float or boolean in turn. Integer and float columns widen to `Float`,
+/// any other mix falls back to `Text`. Empty cells are ignored, and a column without any sampled values is `Text`.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  reading the CSV fails.
+pub fn infer_csv_schema(
+    path: impl AsRef<Path>,
+    sample_rows: usize,
+) -> Result<Vec<InferredType>, Error> {
+    let map_err = |source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    };
+    let file = open_file(&path)?;
+    let mut reader = CsvReader::default().csv_reader(file);
+    let mut inferred: Vec<Option<InferredType>> =
+        vec![None; reader.headers().map_err(map_err)?.len()];
+
+    for record in reader.records().take(sample_rows) {
+        let record = record.map_err(map_err)?;
+        for (column, cell) in inferred.iter_mut().zip(record.iter()) {
+            let cell = cell.trim();
+            if cell.is_empty() {
+                continue;
+            }
+            let cell_type = InferredType::of_cell(cell);
+            *column = Some(column.map_or(cell_type, |t| t.merge(cell_type)));
+        }
+    }
+
+    Ok(inferred
+        .into_iter()
+        .map(|t| t.unwrap_or(InferredType::Text))
+        .collect())
+}
+
+/// Matrix transposition.
+///
+/// Returns a transposed copy of the original matrix. Works with slices.
+/// Inner values are cloned, so can be expensive.
+fn transpose<T>(matrix: &[Vec<T>]) -> Vec<Vec<T>>
+where
+    T: Clone,
+{
+    if matrix.is_empty() {
+        return matrix.to_vec();
+    }
+    // Create the empty transposed array.
+    let mut transposed = vec![vec![]; matrix[0].len()];
+    // Fill the transposed array by copying from the matrix.
+    for original_row in matrix {
+        for (item, transposed_row) in original_row.iter().zip(&mut transposed) {
+            transposed_row.push(item.clone());
+        }
+    }
+
+    transposed
+}
+
+/// Deserialize n-dimensional CSV data from a provided path into nested Vectors.
+///
+/// Each column vector represents a column of the CSV.
+/// Requires the CSV to be standard, with a header value for each field.
+/// All fields must be of the same type.
+/// The data type must be specified by the caller.
+/// A header-only CSV produces one empty column per header.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  deserialization fails.
+pub fn deserialize_csv_column_vectors_from_path<T>(
+    path: impl AsRef<Path>,
+) -> Result<Vec<Vec<T>>, Error>
+where
+    T: for<'a> Deserialize<'a> + Clone,
+{
+    let map_err = |source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    };
+    let file = open_file(&path)?;
+    let mut reader = CsvReader::default().csv_reader(file);
+    let columns = reader.headers().map_err(map_err)?.len();
+    let rows = reader
+        .deserialize::<Vec<T>>()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(map_err)?;
+
+    if rows.is_empty() {
+        // Keep the shape of the schema, rather than an empty outer vector.
+        return Ok(vec![vec![]; columns]);
+    }
+
+    Ok(transpose(&rows))
+}
+
+/// Deserialize numeric CSV data from a provided path directly into a 2-D array of shape `(rows, columns)`.
+///
+/// Requires the `ndarray` feature.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  the rows differ in length.
+/// *  deserialization fails.
+#[cfg(feature = "ndarray")]
+pub fn deserialize_csv_to_array2_from_path(
+    path: impl AsRef<Path>,
+) -> Result<ndarray::Array2<f64>, Error> {
+    let map_err = |source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    };
+    let file = open_file(&path)?;
+    let mut reader = CsvReader::default().csv_reader(file);
+    let columns = reader.headers().map_err(map_err)?.len();
+
+    // Row-major, the reader rejects rows of differing length.
+    let mut values = vec![];
+    let mut rows = 0;
+    for row in reader.deserialize::<Vec<f64>>() {
+        values.extend(row.map_err(map_err)?);
+        rows += 1;
+    }
+
+    ndarray::Array2::from_shape_vec((rows, columns), values).map_err(|e| Error::InvalidShape {
+        path: path.as_ref().into(),
+        msg: e.to_string(),
+    })
+}
+
+/// Deserialize numeric CSV data from a provided path into a map of header name to column vector.
+///
+/// Requires the CSV to be standard, with a unique header value for each field.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  a header name is duplicated.
+/// *  deserialization fails.
+pub fn deserialize_csv_to_column_map_from_path(
+    path: impl AsRef<Path>,
+) -> Result<HashMap<String, Vec<f64>>, Error> {
+    let map_err = |source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    };
+    let file = open_file(&path)?;
+    let mut reader = CsvReader::default().csv_reader(file);
+    let headers = reader.headers().map_err(map_err)?.clone();
+    // Checked before any rows are read, so a bad file fails fast.
+    let mut seen = BTreeSet::new();
+    if let Some(header) = headers.iter().find(|&header| !seen.insert(header)) {
+        return Err(Error::DuplicateHeader {
+            path: path.as_ref().into(),
+            header: header.to_string(),
+        });
+    }
+
+    let mut columns = vec![vec![]; headers.len()];
+    for row in reader.deserialize::<Vec<f64>>() {
+        for (column, value) in columns.iter_mut().zip(row.map_err(map_err)?) {
+            column.push(value);
+        }
+    }
+
+    Ok(headers.iter().map(str::to_string).zip(columns).collect())
+}
+
+/// Splits the CSV at `input` into one single-column CSV per column in `out_dir`, each named after its header.
+///
+/// e.g. a `temperature` column is written to `out_dir/temperature.csv`. Cells are copied verbatim.
+/// `out_dir` is created if missing, and the created paths are returned in column order.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `input` fails.
+/// *  a header is duplicated, empty, or not usable as a file name.
+/// *  creating `out_dir` or any output fails, e.g. it already exists.
+pub fn split_csv_columns_to_files(input: &Path, out_dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let map_err = |source| FileIoError {
+        path: input.into(),
+        source,
+    };
+    let mut reader = CsvReader::default().csv_reader(open_file(input)?);
+    let headers = reader.headers().map_err(map_err)?.clone();
+
+    let mut paths: Vec<PathBuf> = vec![];
+    for header in &headers {
+        if header.is_empty() || header.contains(['/', '\\']) || header == "." || header == ".." {
+            return Err(Error::InvalidValue {
+                path: input.into(),
+                msg: format!("header `{header}` is not usable as a file name"),
+            });
+        }
+        let path = out_dir.join(format!("{header}.csv"));
+        if paths.contains(&path) {
+            return Err(Error::DuplicateHeader {
+                path: input.into(),
+                header: header.to_string(),
+            });
+        }
+        paths.push(path);
+    }
+
+    create_directory(out_dir)?;
+    let mut writers = vec![];
+    for (header, path) in headers.iter().zip(&paths) {
+        let mut writer = csv::Writer::from_writer(create_buffered_file_writer(path)?);
+        writer
+            .write_record([header])
+            .map_err(|source| FileIoError {
+                path: path.as_path().into(),
+                source,
+            })?;
+        writers.push(writer);
+    }
+    for record in reader.records() {
+        let record = record.map_err(map_err)?;
+        for ((writer, cell), path) in writers.iter_mut().zip(&record).zip(&paths) {
+            writer.write_record([cell]).map_err(|source| FileIoError {
+                path: path.as_path().into(),
+                source,
+            })?;
+        }
+    }
+    for (writer, path) in writers.iter_mut().zip(&paths) {
+        writer.flush().map_err(|source| FileIoError {
+            path: path.as_path().into(),
+            source,
+        })?;
+    }
+
+    Ok(paths)
+}
+
+/// Summary statistics of a single CSV column.
+///
+/// `NaN` values are excluded from `min`, `max`, `mean` and `count`, and tallied in `nan_count` instead.
+/// A column without any non-`NaN` values has `NaN` for `min`, `max` and `mean`.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub struct ColumnStats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub count: usize,
+    pub nan_count: usize,
+}
+
+impl Default for ColumnStats {
+    fn default() -> Self {
+        Self {
+            min: f64::NAN,
+            max: f64::NAN,
+            mean: f64::NAN,
+            count: 0,
+            nan_count: 0,
+        }
+    }
+}
+
+/// Deserialize CSV data from a provided path into column vectors, computing `ColumnStats` for each column.
+///
+/// The statistics are accumulated during the single read pass, rather than with a second pass over the columns.
+/// Requires the CSV to be standard, with a header value for each field.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  deserialization fails.
+pub fn deserialize_csv_columns_with_stats_from_path(
+    path: impl AsRef<Path>,
+) -> Result<(Vec<Vec<f64>>, Vec<ColumnStats>), Error> {
+    // Open the file containing the data.
+    let file = open_file(&path)?;
+    let mut reader = CsvReader::default().csv_reader(file);
+
+    let mut columns: Vec<Vec<f64>> = vec![];
+    let mut stats: Vec<ColumnStats> = vec![];
+    let mut sums: Vec<f64> = vec![];
+
+    for result in reader.deserialize::<Vec<f64>>() {
+        let row = result.map_err(|source| FileIoError {
+            path: path.as_ref().into(),
+            source,
+        })?;
+        // The reader is not flexible, so the first row fixes the number of columns.
+        if columns.is_empty() {
+            columns = vec![vec![]; row.len()];
+            stats = vec![ColumnStats::default(); row.len()];
+            sums = vec![0.; row.len()];
+        }
+
+        for (i, value) in row.into_iter().enumerate() {
+            columns[i].push(value);
+            let stat = &mut stats[i];
+            if value.is_nan() {
+                stat.nan_count += 1;
+                continue;
+            }
+            // `f64::min`/`f64::max` ignore the initial `NaN`.
+            stat.min = stat.min.min(value);
+            stat.max = stat.max.max(value);
+            stat.count += 1;
+            sums[i] += value;
+        }
+    }
+
+    for (stat, sum) in stats.iter_mut().zip(sums) {
+        if stat.count > 0 {
+            #[allow(clippy::cast_precision_loss)]
+            let count = stat.count as f64;
+            stat.mean = sum / count;
+        }
+    }
+
+    Ok((columns, stats))
+}
+
+/// A per-column reduction applied by `csv_column_aggregates_from_path`.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Reducer {
+    /// Sum of the column, skipping `NaN` values; `0` if there are none.
+    Sum,
+    /// Mean of the column, skipping `NaN` values; `NaN` if there are none.
+    Mean,
+    /// Minimum of the column, skipping `NaN` values; `NaN` if there are none.
+    Min,
+    /// Maximum of the column, skipping `NaN` values; `NaN` if there are none.
+    Max,
+}
+
+/// Running state of a `Reducer` over one column.
+#[derive(Debug, Clone, Copy)]
+struct ReducerState {
+    reducer: Reducer,
+    value: f64,
+    count: usize,
+}
+
+impl ReducerState {
+    fn new(reducer: Reducer) -> Self {
+        let value = match reducer {
+            Reducer::Sum | Reducer::Mean => 0.,
+            Reducer::Min | Reducer::Max => f64::NAN,
+        };
+        Self {
+            reducer,
+            value,
+            count: 0,
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        // `f64::min`/`f64::max` ignore the initial `NaN`.
+        self.value = match self.reducer {
+            Reducer::Sum | Reducer::Mean => self.value + value,
+            Reducer::Min => self.value.min(value),
+            Reducer::Max => self.value.max(value),
+        };
+        self.count += 1;
+    }
+
+    fn finish(self) -> f64 {
+        match self.reducer {
+            Reducer::Mean if self.count == 0 => f64::NAN,
+            #[allow(clippy::cast_precision_loss)]
+            Reducer::Mean => self.value / self.count as f64,
+            Reducer::Sum | Reducer::Min | Reducer::Max => self.value,
+        }
+    }
+}
+
+/// Reduce each column of the CSV data at the provided path in a single streaming pass.
+///
+/// `reducers` holds one `Reducer` per column, and one value is returned per column. Only the running aggregates
+/// are kept in memory, never the columns, so this suits files too large to read with
+/// `deserialize_csv_column_vectors_from_path`.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+/// *  the number of `reducers` differs from the number of columns.
+/// *  deserialization fails.
+pub fn csv_column_aggregates_from_path(
+    path: impl AsRef<Path>,
+    reducers: &[Reducer],
+) -> Result<Vec<f64>, Error> {
+    // Open the file containing the data.
+    let file = open_file(&path)?;
+    let mut reader = CsvReader::default().csv_reader(file);
+
+    let columns = reader
+        .headers()
+        .map_err(|source| FileIoError {
+            path: path.as_ref().into(),
+            source,
+        })?
+        .len();
+    if columns != reducers.len() {
+        return Err(Error::InvalidShape {
+            path: path.as_ref().into(),
+            msg: format!("expected {} columns, found {columns}", reducers.len()),
+        });
+    }
+
+    let mut states: Vec<ReducerState> = reducers.iter().copied().map(ReducerState::new).collect();
+    for result in reader.deserialize::<Vec<f64>>() {
+        let row = result.map_err(|source| FileIoError {
+            path: path.as_ref().into(),
+            source,
+        })?;
+        for (state, value) in states.iter_mut().zip(row) {
+            state.push(value);
+        }
+    }
+
+    Ok(states.into_iter().map(ReducerState::finish).collect())
+}
+
+/// Returns a `Vector<PathBuf>` containing all files within the provided directory.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` fails.
+pub fn collect_files_from_dir_path(path: impl AsRef<Path>) -> Result<Vec<PathBuf>, Error> {
+    let mut out = vec![];
+    let dir_entries = read_dir(&path).map_err(|source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    })?;
+
+    for file in dir_entries {
+        let file = file.map_err(|source| FileIoError {
+            path: path.as_ref().into(),
+            source,
+        })?;
+
+        // Only process files, not nested directories, etc.
+        if file.metadata()?.is_file() {
+            out.push(file.path());
+        }
+    }
+
+    Ok(out)
+}
+
+/// Size and file count summary of a directory tree.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Default, Clone, Copy)]
+pub struct DirSummary {
+    /// Number of regular files in the tree.
+    pub file_count: u64,
+    /// Total size of the regular files in bytes.
+    pub total_bytes: u64,
+    /// Number of directories in the tree, excluding the root.
+    pub dir_count: u64,
+}
+
+/// Recursively walks the provided directory, summarising the number of files, directories and bytes it contains.
+///
+/// Symlinks are not followed (and are not counted), to avoid double-counting.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` or any nested directory fails.
+pub fn dir_summary(path: impl AsRef<Path>) -> Result<DirSummary, Error> {
+    let mut summary = DirSummary::default();
+    walk_dir(path.as_ref(), &mut |entry_path, file_type| {
+        if file_type.is_file() {
+            summary.file_count += 1;
+            summary.total_bytes += entry_path
+                .symlink_metadata()
+                .map_err(|source| FileIoError {
+                    path: entry_path.into(),
+                    source,
+                })?
+                .len();
+        } else if file_type.is_dir() {
+            summary.dir_count += 1;
+        }
+        Ok(())
+    })?;
+
+    Ok(summary)
+}
+
+/// Differences between two directory trees, as found by `compare_dirs`.
+///
+/// Paths are relative to the compared directories, in sorted order.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Default, Clone)]
+pub struct DirDiff {
+    /// Files in the expected directory, but not the actual one.
+    pub missing: Vec<PathBuf>,
+    /// Files in the actual directory, but not the expected one.
+    pub extra: Vec<PathBuf>,
+    /// Files in both directories with different contents.
+    pub differing: Vec<PathBuf>,
+}
+
+impl DirDiff {
+    /// Returns true if the directories matched.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.differing.is_empty()
+    }
+}
+
+/// Recursively compares a directory of generated output against a golden directory, e.g. in end-to-end tests.
+///
+/// Files are matched by their path relative to each directory. JSON (".json") files are compared semantically, so
+/// formatting and key order don't matter. CSV (".csv") files are compared numerically as in
+/// `csv_files_approx_equal`, within `float_tolerance`. All other files are compared byte-for-byte.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening either directory, or any of the files, fails.
+/// *  a JSON or CSV file present in both directories fails to deserialize.
+pub fn compare_dirs(
+    actual: &Path,
+    expected: &Path,
+    float_tolerance: f64,
+) -> Result<DirDiff, Error> {
+    let actual_files = relative_files(actual)?;
+    let expected_files = relative_files(expected)?;
+
+    let mut diff = DirDiff {
+        missing: expected_files.difference(&actual_files).cloned().collect(),
+        extra: actual_files.difference(&expected_files).cloned().collect(),
+        differing: vec![],
+    };
+    for relative in actual_files.intersection(&expected_files) {
+        let a = actual.join(relative);
+        let b = expected.join(relative);
+        let equal = match relative.extension().and_then(OsStr::to_str) {
+            Some("json") => {
+                deserialize_json_from_path::<serde_json::Value>(&a)?
+                    == deserialize_json_from_path::<serde_json::Value>(&b)?
+            }
+            Some("csv") => csv_files_approx_equal(&a, &b, float_tolerance)?,
+            _ => {
+                let read = |path: &Path| {
+                    std::fs::read(path).map_err(|source| FileIoError {
+                        path: path.into(),
+                        source,
+                    })
+                };
+                read(&a)? == read(&b)?
+            }
+        };
+        if !equal {
+            diff.differing.push(relative.clone());
+        }
+    }
+
+    Ok(diff)
+}
+
+/// Collects the paths of all regular files below the provided directory, relative to it.
+fn relative_files(path: &Path) -> Result<BTreeSet<PathBuf>, Error> {
+    let mut out = BTreeSet::new();
+    walk_dir(path, &mut |entry_path, file_type| {
+        if file_type.is_file() {
+            // `walk_dir` only visits entries below `path`.
+            if let Ok(relative) = entry_path.strip_prefix(path) {
+                let _ = out.insert(relative.to_path_buf());
+            }
+        }
+        Ok(())
+    })?;
+
+    Ok(out)
+}
+
+/// Recursively visits every entry below the provided directory, without following symlinks.
+///
+/// Directories are visited before their contents.
+fn walk_dir(
+    path: &Path,
+    visit: &mut impl FnMut(&Path, std::fs::FileType) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let dir_entries = read_dir(path).map_err(|source| FileIoError {
+        path: path.into(),
+        source,
+    })?;
+
+    for entry in dir_entries {
+        let entry = entry.map_err(|source| FileIoError {
+            path: path.into(),
+            source,
+        })?;
+        let entry_path = entry.path();
+        // `DirEntry::file_type` does not traverse symlinks.
+        let file_type = entry.file_type().map_err(|source| FileIoError {
+            path: entry_path.as_path().into(),
+            source,
+        })?;
+
+        visit(&entry_path, file_type)?;
+        if file_type.is_dir() {
+            walk_dir(&entry_path, visit)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Deserializes n-dimensional data from all CSV (".csv") files in a provided directory path into one nested Vector.
+/// # Errors
+///
+/// Returns an error if:
+///
+/// *  opening `path` or any of the CSV files fails.
+/// *  deserialization fails.
+pub fn deserialize_csv_rows_from_dir_path<T>(
+    path: impl AsRef<Path>,
+) -> Result<Vec<Vec<Vec<T>>>, Error>
+where
+    T: for<'a> Deserialize<'a> + Clone,
+{
+    collect_files_from_dir_path(path)?
+        .iter()
+        .filter(|file| file.extension() == Some(OsStr::new("csv")))
+        .map(|file| deserialize_csv_column_vectors_from_path::<T>(file))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn test_deserialize_csv_rows_from_path() {
+        let expected = vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0], vec![
+            7.0, 8.0, 9.0,
+        ]];
+        let result = deserialize_csv_rows_from_path::<Vec<f64>>("tests/example1.csv").unwrap();
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    #[should_panic(expected = "ParseCsv")]
+    fn test_deserialize_csv_rows_from_path_malformed() {
+        // Data values are floats, attempt to serialize to unsigned ints will fail.
+        let e = deserialize_csv_rows_from_path::<Vec<u64>>("tests/example1.csv");
+        dbg!(&e);
+        let _ = e.unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "FileIo")]
+    fn test_deserialize_csv_rows_from_path_eexist() {
+        // Path does not exist.
+        let e = deserialize_csv_rows_from_path::<Vec<f64>>("tests/non_exist");
+        dbg!(&e);
+        let _ = e.unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "ParseCsv")]
+    fn test_deserialize_csv_rows_from_path_missing() {
+        // Missing field in one row.
+        let e = deserialize_csv_rows_from_path::<Vec<f64>>("tests/bad/missing_field.csv");
+        dbg!(&e);
+        let _ = e.unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidType")]
+    fn test_deserialize_csv_rows_from_path_nonfile() {
+        // `test_dir` is a directory, not a file.
+        let e = deserialize_csv_rows_from_path::<Vec<f64>>("tests");
+        dbg!(&e);
+        let _ = e.unwrap();
+    }
+
+    #[test]
+    fn test_deserialize_csv_rows_from_dir_path() {
+        //
+        let e = deserialize_csv_rows_from_dir_path::<f64>("tests");
+        dbg!(&e);
+        let _ = e.unwrap();
+    }
+
+    #[test]
+    fn test_deserialize_csv_rows_with_missing_from_path() {
+        let result =
+            deserialize_csv_rows_with_missing_from_path("tests/data/empty_cell.csv", f64::NAN)
+                .unwrap();
+        assert_eq!(3, result.len());
+        assert!(result[1][1].is_nan());
+        assert_eq!(vec![4.0, 6.0], vec![result[1][0], result[1][2]]);
+        assert_eq!(vec![7.0, 8.0, 9.0], result[2]);
+
+        // Malformed values still fail.
+        let e = deserialize_csv_rows_with_missing_from_path("tests/bad/wrong_type.csv", f64::NAN);
+        assert!(matches!(e, Err(Error::ParseCsv(_))));
+    }
+
+    #[test]
+    fn test_sample_csv_rows_from_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rows.csv");
+        let rows: Vec<String> = (0..100).map(|i| i.to_string()).collect();
+        std::fs::write(&path, format!("index\n{}\n", rows.join("\n"))).unwrap();
+
+        let first = sample_csv_rows_from_path::<u64>(&path, 10, 42).unwrap();
+        let second = sample_csv_rows_from_path::<u64>(&path, 10, 42).unwrap();
+        assert_eq!(10, first.len());
+        assert_eq!(first, second);
+        let other_seed = sample_csv_rows_from_path::<u64>(&path, 10, 7).unwrap();
+        assert_ne!(first, other_seed);
+
+        let all = sample_csv_rows_from_path::<u64>(&path, 1000, 42).unwrap();
+        assert_eq!((0..100).collect::<Vec<u64>>(), all);
+
+        let huge = sample_csv_rows_from_path::<u64>(&path, usize::MAX, 42).unwrap();
+        assert_eq!(all, huge);
+    }
+
+    #[test]
+    fn test_infer_csv_schema() {
+        let expected = vec![
+            InferredType::Integer,
+            InferredType::Float,
+            InferredType::Text,
+            InferredType::Boolean,
+        ];
+        let result = infer_csv_schema("tests/data/mixed_types.csv", 10).unwrap();
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_deserialize_csv_rows_skip_trailing_from_path() {
+        let expected = vec![vec![1.0, 2.0], vec![3.0, 4.0]];
+        let result =
+            deserialize_csv_rows_skip_trailing_from_path::<Vec<f64>>("tests/data/footer.csv", 1)
+                .unwrap();
+        assert_eq!(expected, result);
+
+        let e =
+            deserialize_csv_rows_skip_trailing_from_path::<Vec<f64>>("tests/data/footer.csv", 0);
+        assert!(matches!(e, Err(Error::ParseCsv(_))));
+    }
+
+    #[test]
+    fn test_deserialize_csv_rows_with_positions_from_path() {
+        let expected = vec![(3, vec![1.0, 2.0]), (5, vec![3.0, 4.0])];
+        let result =
+            deserialize_csv_rows_with_positions_from_path::<Vec<f64>>("tests/data/commented.csv")
+                .unwrap();
+        assert_eq!(expected, result);
+
+        // Windows line endings, blank lines and a quoted field spanning two lines.
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("positions.csv");
+        std::fs::write(
+            &path,
+            "name,value\r\n# comment\r\n\r\n\"a\r\nb\",1\r\nc,2\r\n# trailing\r\nd,3",
+        )
+        .unwrap();
+        let result = deserialize_csv_rows_with_positions_from_path::<(String, u32)>(&path).unwrap();
+        let lines: Vec<u64> = result.iter().map(|(line, _)| *line).collect();
+        assert_eq!(vec![4, 6, 8], lines);
+        assert_eq!("a\r\nb", result[0].1.0);
+    }
+
+    #[test]
+    fn test_deserialize_csv_rows_checked_from_path() {
+        let result = deserialize_csv_rows_checked_from_path::<Vec<f64>>(
+            "tests/example1.csv",
+            &["x", "y", "z"],
+            true,
+        )
+        .unwrap();
+        assert_eq!(3, result.len());
+
+        let e = deserialize_csv_rows_checked_from_path::<Vec<f64>>(
+            "tests/example1.csv",
+            &["y", "x", "z"],
+            true,
+        );
+        assert!(matches!(e, Err(Error::HeaderMismatch { .. })));
+
+        // Set equality is enough without `check_order`.
+        let result = deserialize_csv_rows_checked_from_path::<Vec<f64>>(
+            "tests/example1.csv",
+            &["y", "x", "z"],
+            false,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_deserialize_csv_multiheader_from_path() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Reading {
+            time: f64,
+            temperature: f64,
+        }
+
+        let (headers, rows) =
+            deserialize_csv_multiheader_from_path::<Reading>("tests/data/units.csv", 2).unwrap();
+        assert_eq!(vec![vec!["time", "temperature"], vec!["s", "K"]], headers);
+        assert_eq!(
+            vec![
+                Reading {
+                    time: 0.,
+                    temperature: 273.15
+                },
+                Reading {
+                    time: 1.,
+                    temperature: 274.15
+                }
+            ],
+            rows
+        );
+    }
+
+    #[test]
+    fn test_deserialize_csv_rows_renamed_from_path() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Observation {
+            air_temperature: f64,
+            #[serde(rename = "Station")]
+            station: String,
+        }
+
+        let rename =
+            HashMap::from([("Air Temperature".to_string(), "air_temperature".to_string())]);
+        let result = deserialize_csv_rows_renamed_from_path::<Observation, _>(
+            "tests/data/title_case.csv",
+            &rename,
+        )
+        .unwrap();
+        let expected = vec![
+            Observation {
+                air_temperature: 273.5,
+                station: "north".to_string(),
+            },
+            Observation {
+                air_temperature: 280.25,
+                station: "south".to_string(),
+            },
+        ];
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_deserialize_csv_rows_from_reader() {
+        let data = b"x,y,z\n1.,2.,3.\n# comment\n4.,5.,6.\n";
+        let expected = vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]];
+        let result = deserialize_csv_rows_from_reader::<_, Vec<f64>>(&data[..]).unwrap();
+        assert_eq!(expected, result);
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Point {
+        x: f64,
+        label: String,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq, Clone)]
+    struct Xyz {
+        x: f64,
+        y: f64,
+        z: f64,
+    }
+
+    #[test]
+    fn test_csv_struct_stream() {
+        let expected = deserialize_csv_rows_from_path::<Xyz>("tests/example1.csv").unwrap();
+        let result = csv_struct_stream::<Xyz>("tests/example1.csv")
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_serialize_json_sorted_to_path() {
+        #[derive(Serialize)]
+        struct Before {
+            b: f64,
+            a: Vec<u8>,
+            c: Point2,
+        }
+        #[derive(Serialize)]
+        struct After {
+            c: Point2,
+            a: Vec<u8>,
+            b: f64,
+        }
+        #[derive(Serialize)]
+        struct Point2 {
+            y: f64,
+            x: f64,
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let before = dir.path().join("before.json");
+        let after = dir.path().join("after.json");
+        serialize_json_sorted_to_path(
+            &Before {
+                b: 1.5,
+                a: vec![1, 2],
+                c: Point2 { y: 2., x: 1. },
+            },
+            &before,
+        )
+        .unwrap();
+        serialize_json_sorted_to_path(
+            &After {
+                c: Point2 { y: 2., x: 1. },
+                a: vec![1, 2],
+                b: 1.5,
+            },
+            &after,
+        )
+        .unwrap();
+
+        let before = std::fs::read(before).unwrap();
+        assert_eq!(before, std::fs::read(after).unwrap());
+        assert!(String::from_utf8(before).unwrap().starts_with("{\n  \"a\""));
+    }
+
+    #[test]
+    #[cfg(feature = "npy")]
+    fn test_serialize_matrix_to_npy() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("matrix.npy");
+        serialize_matrix_to_npy(&[vec![1., 2., 3.], vec![4., 5., 6.5]], &path).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(b"\x93NUMPY\x01\x00", &bytes[..8]);
+        assert_eq!(118, u16::from_le_bytes([bytes[8], bytes[9]]));
+        let header = std::str::from_utf8(&bytes[10..128]).unwrap();
+        assert_eq!(
+            "{'descr': '<f8', 'fortran_order': False, 'shape': (2, 3), }",
+            header.trim_end()
+        );
+        assert!(header.ends_with(" \n"));
+        let values: Vec<f64> = bytes[128..]
+            .chunks_exact(8)
+            .map(|b| f64::from_le_bytes(b.try_into().unwrap()))
+            .collect();
+        assert_eq!(vec![1., 2., 3., 4., 5., 6.5], values);
+
+        let ragged = dir.path().join("ragged.npy");
+        let e = serialize_matrix_to_npy(&[vec![1., 2.], vec![3.]], &ragged);
+        assert!(matches!(e, Err(Error::InvalidShape { .. })));
+        assert!(!ragged.exists());
+    }
+
+    #[test]
+    #[cfg(feature = "ndarray")]
+    fn test_deserialize_csv_to_array2_from_path() {
+        let array = deserialize_csv_to_array2_from_path("tests/example1.csv").unwrap();
+        assert_eq!(&[3, 3], array.shape());
+        assert_eq!(
+            vec![2., 7., 9.],
+            vec![array[[0, 1]], array[[2, 0]], array[[2, 2]]]
+        );
+
+        let e = deserialize_csv_to_array2_from_path("tests/bad/ragged_rows.csv");
+        assert!(matches!(
+            e,
+            Err(Error::ParseCsv(FileIoError { ref source, .. }))
+                if matches!(source.kind(), csv::ErrorKind::UnequalLengths { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_deserialize_json_from_dir_path_parallel() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..20 {
+            std::fs::write(
+                dir.path().join(format!("{i:02}.json")),
+                format!(r#"{{"x": {i}, "label": "{i}"}}"#),
+            )
+            .unwrap();
+        }
+        let expected = deserialize_json_from_dir_path::<Point>(dir.path()).unwrap();
+        let result = deserialize_json_from_dir_path_parallel::<Point>(dir.path()).unwrap();
+        assert_eq!(20, result.len());
+        assert_eq!(expected, result);
+
+        // The first malformed file in sorted order is reported.
+        std::fs::write(dir.path().join("05.json"), "{").unwrap();
+        std::fs::write(dir.path().join("15.json"), "x").unwrap();
+        let e = deserialize_json_from_dir_path_parallel::<Point>(dir.path()).unwrap_err();
+        assert!(
+            matches!(e, Error::ParseJsonFile(e) if e.path.ends_with("05.json") && e.source.is_eof())
+        );
+    }
+
+    #[test]
+    fn test_deserialize_json_merged_from_paths() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Config {
+            name: String,
+            point: Point,
+            tags: Vec<String>,
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let defaults = dir.path().join("defaults.json");
+        let overrides = dir.path().join("overrides.json");
+        let missing = dir.path().join("missing.json");
+        std::fs::write(
+            &defaults,
+            r#"{"name": "run", "point": {"x": 1.0, "label": "a"}, "tags": ["a", "b"]}"#,
+        )
+        .unwrap();
+        std::fs::write(&overrides, r#"{"point": {"x": 2.0}, "tags": ["c"]}"#).unwrap();
+
+        let expected = Config {
+            name: "run".to_string(),
+            point: Point {
+                x: 2.0,
+                label: "a".to_string(),
+            },
+            tags: vec!["c".to_string()],
+        };
+        let paths = [defaults.as_path(), missing.as_path(), overrides.as_path()];
+        let result: Config = deserialize_json_merged_from_paths(&paths, true).unwrap();
+        assert_eq!(expected, result);
+
+        let e = deserialize_json_merged_from_paths::<Config>(&paths, false);
+        assert!(matches!(e, Err(Error::FileIo(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "preserve_order")]
+    fn test_deserialize_json_preserving_order_from_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input.json");
+        let output = dir.path().join("output.json");
+        std::fs::write(&input, r#"{"z": 1, "a": {"y": 2, "b": 3}, "m": 4}"#).unwrap();
+
+        let mut value = deserialize_json_preserving_order_from_path(&input).unwrap();
+        value["a"]["b"] = serde_json::json!(5);
+        serialize_json_to_path(&value, &output).unwrap();
+
+        let result: String = std::fs::read_to_string(&output)
+            .unwrap()
+            .split_whitespace()
+            .collect();
+        assert_eq!(r#"{"z":1,"a":{"y":2,"b":5},"m":4}"#, result);
+    }
+
+    #[test]
+    fn test_deserialize_json_from_str() {
+        let expected = Point {
+            x: 1.5,
+            label: "a".to_string(),
+        };
+        let result: Point = deserialize_json_from_str(r#"{"x": 1.5, "label": "a"}"#).unwrap();
+        assert_eq!(expected, result);
+
+        let result: Vec<Point> =
+            deserialize_json_from_str(r#"[{"x": 1.5, "label": "a"}, {"x": 2.0, "label": "b"}]"#)
+                .unwrap();
+        assert_eq!(2, result.len());
+        assert_eq!(expected, result[0]);
+    }
+
+    #[test]
+    fn test_deserialize_csv_to_column_map_from_path() {
+        let result = deserialize_csv_to_column_map_from_path("tests/example1.csv").unwrap();
+        let expected = HashMap::from([
+            ("x".to_string(), vec![1.0, 4.0, 7.0]),
+            ("y".to_string(), vec![2.0, 5.0, 8.0]),
+            ("z".to_string(), vec![3.0, 6.0, 9.0]),
+        ]);
+        assert_eq!(expected, result);
+
+        let e = deserialize_csv_to_column_map_from_path("tests/bad/duplicate_header.csv");
+        assert!(matches!(e, Err(Error::DuplicateHeader { header, .. }) if header == "x"));
+
+        // The header is checked before any rows are read.
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad_rows.csv");
+        std::fs::write(&path, "x,x\n1,oops\n").unwrap();
+        let e = deserialize_csv_to_column_map_from_path(&path);
+        assert!(matches!(e, Err(Error::DuplicateHeader { .. })));
+    }
+
+    #[test]
+    fn test_deserialize_csv_columns_with_stats_from_path() {
+        let (columns, stats) =
+            deserialize_csv_columns_with_stats_from_path("tests/example1.csv").unwrap();
+        let expected =
+            deserialize_csv_column_vectors_from_path::<f64>("tests/example1.csv").unwrap();
+        assert_eq!(expected, columns);
+
+        let expected =
+            [(1., 7., 4.), (2., 8., 5.), (3., 9., 6.)].map(|(min, max, mean)| ColumnStats {
+                min,
+                max,
+                mean,
+                count: 3,
+                nan_count: 0,
+            });
+        assert_eq!(expected.to_vec(), stats);
+    }
+
+    #[test]
+    fn test_csv_column_aggregates_from_path() {
+        let means =
+            csv_column_aggregates_from_path("tests/example1.csv", &[Reducer::Mean; 3]).unwrap();
+        assert_eq!(vec![4., 5., 6.], means);
+
+        let reducers = [Reducer::Sum, Reducer::Min, Reducer::Max];
+        let aggregates = csv_column_aggregates_from_path("tests/example1.csv", &reducers).unwrap();
+        assert_eq!(vec![12., 2., 9.], aggregates);
+
+        let e = csv_column_aggregates_from_path("tests/example1.csv", &[Reducer::Mean]);
+        assert!(matches!(e, Err(Error::InvalidShape { .. })));
+    }
+
+    crate::csv_columns! {
+        #[derive(Debug, PartialEq)]
+        struct MixedColumns {
+            id: Vec<u32>,
+            value: Vec<f64>,
+            label: Vec<String>,
+        }
+    }
+
+    #[test]
+    fn test_deserialize_csv_into_struct_of_vecs() {
+        let columns: MixedColumns =
+            deserialize_csv_into_struct_of_vecs("tests/data/mixed_types.csv").unwrap();
+        let expected = MixedColumns {
+            id: vec![1, 2, 3],
+            value: vec![0.5, 1., 2.25],
+            label: vec!["alpha".into(), "beta".into(), "gamma".into()],
+        };
+        assert_eq!(expected, columns);
+
+        let e = deserialize_csv_into_struct_of_vecs::<MixedColumns>("tests/example1.csv");
+        assert!(matches!(e, Err(Error::HeaderMismatch { .. })));
+    }
+
+    #[test]
+    fn test_read_lines_normalized() {
+        let lines = read_lines_normalized("tests/data/mixed_endings.csv").unwrap();
+        assert_eq!(vec!["x,label", "1,a", "2,b", "3,c", "4,d"], lines);
+    }
+
+    #[test]
+    fn test_deserialize_csv_rows_from_path_mixed_endings() {
+        let rows: Vec<(u32, String)> =
+            deserialize_csv_rows_from_path("tests/data/mixed_endings.csv").unwrap();
+        let labels: Vec<&str> = rows.iter().map(|(_, label)| label.as_str()).collect();
+        assert_eq!(vec!["a", "b", "c", "d"], labels);
+    }
+
+    #[test]
+    fn test_deserialize_csv_numeric_with_grouping_from_path() {
+        let rows: Vec<(String, f64, f64)> =
+            deserialize_csv_numeric_with_grouping_from_path("tests/data/grouped.csv", b';', ',')
+                .unwrap();
+        let expected = vec![
+            ("north, east".to_string(), 1_234.56, 12.),
+            ("south".to_string(), 1_234_567., 0.5),
+        ];
+        assert_eq!(expected, rows);
+
+        // Labels that aren't grouped numbers are untouched, grouped numbers are stripped whatever the target type.
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("labels.csv");
+        std::fs::write(&path, "a;b;c\n1,2;12,34.5;1,000\n").unwrap();
+        let rows: Vec<(String, String, String)> =
+            deserialize_csv_numeric_with_grouping_from_path(&path, b';', ',').unwrap();
+        assert_eq!(
+            vec![("1,2".to_string(), "12,34.5".to_string(), "1000".to_string())],
+            rows
+        );
+    }
+
+    #[test]
+    fn test_deserialize_csv_column_vectors_from_path_header_only() {
+        let columns =
+            deserialize_csv_column_vectors_from_path::<f64>("tests/data/header_only.csv").unwrap();
+        assert_eq!(vec![Vec::<f64>::new(); 3], columns);
+    }
+
+    #[test]
+    fn test_deserialize_csv_single_row_from_path() {
+        let row: Xyz = deserialize_csv_single_row_from_path("tests/data/single_row.csv").unwrap();
+        assert_eq!(
+            Xyz {
+                x: 1.,
+                y: 2.,
+                z: 3.
+            },
+            row
+        );
+
+        let e = deserialize_csv_single_row_from_path::<Xyz>("tests/bad/two_rows.csv");
+        assert!(matches!(e, Err(Error::InvalidShape { msg, .. }) if msg.ends_with("found 2")));
+    }
+
+    #[test]
+    fn test_deserialize_csv_with_null_tokens_from_path() {
+        // `NaN` compares unequal, so map it to `None` for comparison.
+        let read = |tokens: &[&str]| -> Vec<Vec<Option<f64>>> {
+            deserialize_csv_with_null_tokens_from_path("tests/data/null_tokens.csv", tokens)
+                .unwrap()
+                .into_iter()
+                .map(|row| {
+                    row.into_iter()
+                        .map(|v| (!v.is_nan()).then_some(v))
+                        .collect()
+                })
+                .collect()
+        };
+        let expected = vec![
+            vec![Some(1.), Some(2.5)],
+            vec![None, Some(3.)],
+            vec![Some(4.), None],
+            vec![Some(5.), None],
+        ];
+        assert_eq!(expected, read(&["NA", "-9999"]));
+
+        // Without the sentinel token it is just a number.
+        assert_eq!(vec![Some(4.), Some(-9999.)], read(&["NA"])[2]);
+
+        // Numbers may be padded, and other cells are reported by position.
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("padded.csv");
+        std::fs::write(&path, "a,b\n 3 ,NA\n4,oops\n").unwrap();
+        let e = deserialize_csv_with_null_tokens_from_path(&path, &["NA"]);
+        assert!(
+            matches!(e, Err(Error::InvalidValue { msg, .. }) if msg.contains("row 1 column 1"))
+        );
+        std::fs::write(&path, "a,b\n 3 ,NA\n").unwrap();
+        let rows = deserialize_csv_with_null_tokens_from_path(&path, &["NA"]).unwrap();
+        assert_eq!(Some(3.), rows[0].first().copied());
+        assert!(rows[0][1].is_nan());
+    }
+
+    #[test]
+    fn test_deserialize_csv_deduped_by_column_from_path() {
+        let path = "tests/data/duplicate_keys.csv";
+        let first =
+            deserialize_csv_deduped_by_column_from_path(path, 0, Aggregation::First).unwrap();
+        assert_eq!(
+            vec![vec![1., 10., 1.], vec![2., 20., 2.], vec![3., 30., 3.]],
+            first
+        );
+
+        let last = deserialize_csv_deduped_by_column_from_path(path, 0, Aggregation::Last).unwrap();
+        assert_eq!(
+            vec![vec![1., 14., 5.], vec![2., 22., 4.], vec![3., 30., 3.]],
+            last
+        );
+
+        let mean = deserialize_csv_deduped_by_column_from_path(path, 0, Aggregation::Mean).unwrap();
+        assert_eq!(
+            vec![vec![1., 12., 3.], vec![2., 21., 3.], vec![3., 30., 3.]],
+            mean
+        );
+
+        let e = deserialize_csv_deduped_by_column_from_path(path, 3, Aggregation::First);
+        assert!(matches!(e, Err(Error::InvalidShape { .. })));
+    }
+
+    #[test]
+    fn test_csv_files_approx_equal() {
+        let dir = tempfile::tempdir().unwrap();
+        let tolerance = 1e-6;
+        let write = |name: &str, content: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            path
+        };
+        let golden = write("golden.csv", "x,y\n1,0.1\n2,0.2\n");
+        let same = write("same.csv", "x,y\n1.0,0.10000000001\n2.000,2e-1\n");
+        let off = write("off.csv", "x,y\n1,0.100002\n2,0.2\n");
+        let short = write("short.csv", "x,y\n1,0.1\n");
+
+        assert!(csv_files_approx_equal(&golden, &golden, tolerance).unwrap());
+        assert!(csv_files_approx_equal(&golden, &same, tolerance).unwrap());
+        assert!(!csv_files_approx_equal(&golden, &off, tolerance).unwrap());
+        assert!(!csv_files_approx_equal(&golden, &short, tolerance).unwrap());
+    }
+
+    #[test]
+    fn test_serialize_csv_rows_sharded_to_path() {
+        #[derive(Serialize)]
+        struct Row {
+            x: u32,
+            y: f64,
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let rows: Vec<Row> = (0..5)
+            .map(|x| Row {
+                x,
+                y: f64::from(x) / 2.,
+            })
+            .collect();
+        let paths =
+            serialize_csv_rows_sharded_to_path(&rows, &dir.path().join("out.csv"), 2).unwrap();
+        let names: Vec<_> = paths.iter().map(|path| path.file_name().unwrap()).collect();
+        assert_eq!(vec!["out_0.csv", "out_1.csv", "out_2.csv"], names);
+
+        assert_eq!(
+            "x,y\n0,0.0\n1,0.5\n",
+            std::fs::read_to_string(&paths[0]).unwrap()
+        );
+        assert_eq!("x,y\n4,2.0\n", std::fs::read_to_string(&paths[2]).unwrap());
+    }
+
+    #[test]
+    fn test_csv_reader_builder() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("semicolons.csv");
+        std::fs::write(&path, "#a;2\nb;NA\nc;6\n").unwrap();
+        let rows: Vec<(String, f64)> = CsvReader::builder()
+            .delimiter(b';')
+            .comment(None)
+            .has_headers(false)
+            .null_tokens(&["na"])
+            .build()
+            .read_rows(&path)
+            .unwrap();
+        assert_eq!(3, rows.len());
+        assert_eq!("#a", rows[0].0);
+        assert!(rows[1].1.is_nan());
+        assert_eq!(("c".to_string(), 6.), rows[2]);
+
+        // The default reader matches a plain `csv` reader.
+        let expected: Vec<Xyz> = csv::Reader::from_path("tests/example1.csv")
+            .unwrap()
+            .deserialize()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let rows: Vec<Xyz> = CsvReader::default()
+            .read_rows("tests/example1.csv")
+            .unwrap();
+        assert_eq!(expected, rows);
+
+        // Skipping trailing rows matches dropping them afterwards.
+        let rows: Vec<Xyz> = CsvReader::builder()
+            .skip_trailing(2)
+            .build()
+            .read_rows("tests/example1.csv")
+            .unwrap();
+        assert_eq!(expected[..1], rows);
+
+        // Null tokens may be padded, and numbers only parse once trimmed.
+        let path = dir.path().join("padded.csv");
+        std::fs::write(&path, "a,b\n 3 , NA \n4,-9999\n").unwrap();
+        let rows: Vec<Vec<f64>> = CsvReader::builder()
+            .trim(true)
+            .null_tokens(&["na", "-9999"])
+            .build()
+            .read_rows(&path)
+            .unwrap();
+        assert_eq!(
+            vec![Some(3.), Some(4.)],
+            vec![rows[0].first().copied(), rows[1].first().copied()]
+        );
+        assert!(rows[0][1].is_nan() && rows[1][1].is_nan());
+        let e = CsvReader::builder()
+            .null_tokens(&["NA"])
+            .build()
+            .read_rows::<Vec<f64>>(&path);
+        assert!(
+            matches!(e, Err(Error::InvalidValue { msg, .. }) if msg.contains("row 0 column 0"))
+        );
+
+        // A bad value is reported by position, as by the standalone function.
+        std::fs::write(&path, "a,b\n 3 , NA \n4,oops\n").unwrap();
+        let e = CsvReader::builder()
+            .trim(true)
+            .null_tokens(&["NA"])
+            .build()
+            .read_rows::<Vec<f64>>(&path);
+        assert!(
+            matches!(e, Err(Error::InvalidValue { msg, .. }) if msg.contains("row 1 column 1"))
+        );
+        let e = deserialize_csv_with_null_tokens_from_path(&path, &["NA"]);
+        assert!(
+            matches!(e, Err(Error::InvalidValue { msg, .. }) if msg.contains("row 1 column 1"))
+        );
+
+        let rows: Vec<Xyz> = CsvReader::builder()
+            .skip_trailing(usize::MAX)
+            .build()
+            .read_rows("tests/example1.csv")
+            .unwrap();
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn test_detect_csv_delimiter() {
+        assert_eq!(b',', detect_csv_delimiter("tests/example1.csv").unwrap());
+        assert_eq!(b'\t', detect_csv_delimiter("tests/data/tabs.tsv").unwrap());
+        assert_eq!(
+            b';',
+            detect_csv_delimiter("tests/data/semicolons.csv").unwrap()
+        );
+
+        let rows: Vec<Xyz> =
+            deserialize_csv_rows_auto_from_path("tests/data/semicolons.csv").unwrap();
+        let expected: Vec<Xyz> = deserialize_csv_rows_from_path("tests/example1.csv").unwrap();
+        assert_eq!(expected[..2], rows);
+
+        let e = detect_csv_delimiter("tests/data/single_column.csv");
+        assert!(matches!(e, Err(Error::InvalidValue { .. })));
+    }
+
+    #[test]
+    fn test_deserialize_csv_record() {
+        let headers = csv::StringRecord::from(vec!["label", "x"]);
+        let record = csv::StringRecord::from(vec!["a", "1.5"]);
+        let point: Point = deserialize_csv_record(&record, &headers).unwrap();
+        assert_eq!(
+            Point {
+                x: 1.5,
+                label: "a".to_string()
+            },
+            point
+        );
+
+        let record = csv::StringRecord::from(vec!["a", "one"]);
+        let e = deserialize_csv_record::<Point>(&record, &headers);
+        assert!(matches!(e, Err(Error::ReadCsv(_))));
+    }
+
+    #[test]
+    fn test_validate_run_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let required = ["config.json", "output.jsonl"];
+        std::fs::write(dir.path().join("config.json"), "{}").unwrap();
+        // A directory doesn't count as the file.
+        std::fs::create_dir(dir.path().join("output.jsonl")).unwrap();
+
+        let e = validate_run_directory(dir.path(), &required);
+        assert!(
+            matches!(e, Err(Error::MissingFiles { missing, .. }) if missing == vec!["output.jsonl"])
+        );
+
+        std::fs::remove_dir(dir.path().join("output.jsonl")).unwrap();
+        std::fs::write(dir.path().join("output.jsonl"), "").unwrap();
+        assert!(validate_run_directory(dir.path(), &required).is_ok());
+    }
+
+    #[test]
+    fn test_deserialize_csv_rows_filtered_from_path() {
+        let rows: Vec<Xyz> =
+            deserialize_csv_rows_filtered_from_path("tests/example1.csv", |row: &Xyz| row.y > 4.)
+                .unwrap();
+        let xs: Vec<f64> = rows.iter().map(|row| row.x).collect();
+        assert_eq!(vec![4., 7.], xs);
+    }
+
+    #[test]
+    fn test_backup_then_write_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file.json");
+        assert_eq!(None, backup_then_write_json(&1, &path).unwrap());
+
+        let backup = backup_then_write_json(&2, &path).unwrap().unwrap();
+        assert_eq!(dir.path().join("file.json.bak"), backup);
+        let backup = backup_then_write_json(&3, &path).unwrap().unwrap();
+        assert_eq!(dir.path().join("file.json.bak.1"), backup);
+
+        assert_eq!(3, deserialize_json_from_path::<i32>(&path).unwrap());
+        assert_eq!(2, deserialize_json_from_path::<i32>(&backup).unwrap());
+        let first = dir.path().join("file.json.bak");
+        assert_eq!(1, deserialize_json_from_path::<i32>(first).unwrap());
+    }
+
+    #[test]
+    fn test_read_csv_cells_from_path() {
+        let cells = read_csv_cells_from_path("tests/bad/ragged.csv").unwrap();
+        let expected = vec![
+            vec!["a", "b", "c"],
+            vec!["1", "", "3"],
+            vec!["4"],
+            vec!["#5", "6", "7", "8"],
+        ];
+        assert_eq!(expected, cells);
+    }
+
+    #[test]
+    fn test_merge_sorted_csv_by_column() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("merged.csv");
+        merge_sorted_csv_by_column(
+            &[
+                Path::new("tests/data/day_1.csv"),
+                Path::new("tests/data/day_2.csv"),
+            ],
+            0,
+            &output,
+        )
+        .unwrap();
+
+        let merged = std::fs::read_to_string(&output).unwrap();
+        assert_eq!("t,value\n1,a\n2,b\n3,x\n3,c\n4,y\n10,z\n", merged);
+    }
+
+    #[test]
+    fn test_csv_reader_builder_quote() {
+        let rows: Vec<(String, f64)> = CsvReader::builder()
+            .quote(b'\'')
+            .build()
+            .read_rows("tests/data/single_quoted.csv")
+            .unwrap();
+        let expected = vec![("north, east".to_string(), 1.), ("south".to_string(), 2.)];
+        assert_eq!(expected, rows);
+    }
+
+    #[test]
+    fn test_tail_jsonl() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.jsonl");
+        // No trailing newline.
+        std::fs::write(&path, "1\n2\n3\n\n4\n5").unwrap();
+        assert_eq!(vec![4, 5], tail_jsonl::<i32>(&path, 2).unwrap());
+        assert_eq!(vec![1, 2, 3, 4, 5], tail_jsonl::<i32>(&path, 20).unwrap());
+        assert!(tail_jsonl::<i32>(&path, 0).unwrap().is_empty());
+
+        // Spanning several chunks.
+        let path = dir.path().join("long.jsonl");
+        let lines: Vec<String> = (0..5000).map(|i| format!(r#"{{"x": {i}}}"#)).collect();
+        std::fs::write(&path, lines.join("\n") + "\n").unwrap();
+        let tail: Vec<serde_json::Value> = tail_jsonl(&path, 3).unwrap();
+        assert_eq!(serde_json::json!({"x": 4997}), tail[0]);
+        assert_eq!(serde_json::json!({"x": 4999}), tail[2]);
+        let tail: Vec<serde_json::Value> = tail_jsonl(&path, 4000).unwrap();
+        assert_eq!(4000, tail.len());
+        assert_eq!(serde_json::json!({"x": 1000}), tail[0]);
+
+        // Parse errors carry the path.
+        let path = dir.path().join("bad.jsonl");
+        std::fs::write(&path, "1\n{\n3\n").unwrap();
+        let e = tail_jsonl::<i32>(&path, 2).unwrap_err();
+        assert!(matches!(e, Error::ParseJsonFile(e) if e.path.ends_with("bad.jsonl")));
+    }
+
+    #[test]
+    fn test_append_csv_rows_to_path() {
+        #[derive(Serialize)]
+        struct Row {
+            x: u32,
+            label: &'static str,
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("results.csv");
+        append_csv_rows_to_path(&[Row { x: 1, label: "a" }], &path).unwrap();
+        append_csv_rows_to_path(&[Row { x: 2, label: "b" }, Row { x: 3, label: "c" }], &path)
+            .unwrap();
+        assert_eq!(
+            "x,label\n1,a\n2,b\n3,c\n",
+            std::fs::read_to_string(&path).unwrap()
+        );
+
+        let e = append_csv_rows_to_path(&[Row { x: 1, label: "a" }], dir.path());
+        assert!(matches!(e, Err(Error::InvalidType { .. })));
+    }
+
+    #[test]
+    fn test_split_csv_columns_to_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let out_dir = dir.path().join("columns");
+        let paths = split_csv_columns_to_files(Path::new("tests/example1.csv"), &out_dir).unwrap();
+        let expected: Vec<PathBuf> = ["x", "y", "z"]
+            .iter()
+            .map(|name| out_dir.join(format!("{name}.csv")))
+            .collect();
+        assert_eq!(expected, paths);
+        assert_eq!(
+            "y\n2.\n5.\n8.\n",
+            std::fs::read_to_string(&paths[1]).unwrap()
+        );
+
+        let e = split_csv_columns_to_files(
+            Path::new("tests/bad/duplicate_header.csv"),
+            &dir.path().join("duplicate"),
+        );
+        assert!(matches!(e, Err(Error::DuplicateHeader { .. })));
+    }
+
+    #[test]
+    fn test_deserialize_json_from_path_error_location() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, "{\n  \"x\": 1,\n  \"y\": oops\n}\n").unwrap();
+        let e = deserialize_json_from_path::<serde_json::Value>(&path).unwrap_err();
+        assert!(matches!(e, Error::ParseJsonFile(_)));
+        let msg = e.to_string();
+        assert!(msg.contains(&path.display().to_string()));
+        assert!(msg.contains("line 3, column 8"));
+    }
+
+    #[test]
+    fn test_compare_dirs() {
+        let actual = tempfile::tempdir().unwrap();
+        let expected = tempfile::tempdir().unwrap();
+        for dir in [&actual, &expected] {
+            std::fs::create_dir(dir.path().join("nested")).unwrap();
+            std::fs::write(dir.path().join("notes.txt"), "done\n").unwrap();
+        }
+        std::fs::write(actual.path().join("data.csv"), "x,y\n1.0,2.0\n").unwrap();
+        std::fs::write(expected.path().join("data.csv"), "x,y\n1,2.0000001\n").unwrap();
+        std::fs::write(
+            actual.path().join("nested/config.json"),
+            r#"{"a": 1, "b": 2}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            expected.path().join("nested/config.json"),
+            "{\n  \"b\": 3,\n  \"a\": 1\n}",
+        )
+        .unwrap();
+        std::fs::write(expected.path().join("nested/summary.json"), "{}").unwrap();
+
+        let diff = compare_dirs(actual.path(), expected.path(), 1e-6).unwrap();
+        let expected_diff = DirDiff {
+            missing: vec![PathBuf::from("nested/summary.json")],
+            extra: vec![],
+            differing: vec![PathBuf::from("nested/config.json")],
+        };
+        assert_eq!(expected_diff, diff);
+        assert!(!diff.is_empty());
+
+        let diff = compare_dirs(actual.path(), actual.path(), 0.).unwrap();
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn test_validate_utf8() {
+        validate_utf8("tests/example1.csv").unwrap();
+        let e = validate_utf8("tests/bad/latin1.csv");
+        assert!(matches!(
+            e,
+            Err(Error::Encoding {
+                byte_offset: 16,
+                ..
+            })
+        ));
+
+        // A character split across chunks is valid, but truncated at the end of the file is not.
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("long.csv");
+        let mut data = "a".repeat(8191).into_bytes();
+        data.extend_from_slice("\u{e9}".as_bytes());
+        std::fs::write(&path, &data).unwrap();
+        validate_utf8(&path).unwrap();
+        let _ = data.pop();
+        std::fs::write(&path, &data).unwrap();
+        let e = validate_utf8(&path);
+        assert!(matches!(
+            e,
+            Err(Error::Encoding {
+                byte_offset: 8191,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_csv_row_batches_from_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rows.csv");
+        std::fs::write(&path, "x,y\n1,2\n3,4\n5,6\n7,8\n9,10\n").unwrap();
+        let batches: Vec<Vec<(u32, u32)>> = csv_row_batches_from_path(&path, 2)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let sizes: Vec<usize> = batches.iter().map(Vec::len).collect();
+        assert_eq!(vec![2, 2, 1], sizes);
+        assert_eq!(vec![(9, 10)], batches[2]);
+
+        let batches: Vec<Vec<(u32, u32)>> = csv_row_batches_from_path(&path, usize::MAX)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(1, batches.len());
+        assert_eq!(5, batches[0].len());
+
+        let e = csv_row_batches_from_path::<(u32, u32)>(&path, 0);
+        assert!(matches!(e, Err(Error::InvalidValue { .. })));
+    }
+
+    #[test]
+    fn test_serialize_multi_format() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Artifact {
+            name: String,
+            value: u32,
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("artifact");
+        let data = Artifact {
+            name: "name".to_string(),
+            value: 1,
+        };
+        let paths = serialize_multi_format(&data, &base, &[Format::Json]).unwrap();
+        assert_eq!(vec![base.with_extension("json")], paths);
+        assert_eq!(
+            data,
+            deserialize_json_from_path::<Artifact>(&paths[0]).unwrap()
+        );
+
+        // Nothing is written when any format is unavailable.
+        let base = dir.path().join("partial");
+        let e = serialize_multi_format(&data, &base, &[Format::Json, Format::Yaml]);
+        if cfg!(feature = "yaml") {
+            let paths = e.unwrap();
+            let yaml = std::fs::read_to_string(&paths[1]).unwrap();
+            assert_eq!(base.with_extension("yaml"), paths[1]);
+            assert!(yaml.contains("name: name"));
+        } else {
+            assert!(matches!(e, Err(Error::UnsupportedFormat { .. })));
+            assert!(!base.with_extension("json").exists());
+        }
+    }
+
+    #[test]
+    fn test_read_csv_headers() {
+        assert_eq!(
+            vec!["x", "y", "z"],
+            read_csv_headers("tests/example1.csv").unwrap()
+        );
+        assert_eq!(
+            vec!["Air Temperature", "Station"],
+            read_csv_headers("tests/data/title_case.csv").unwrap()
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("empty.csv");
+        std::fs::write(&path, "").unwrap();
+        let e = read_csv_headers(&path);
+        assert!(matches!(e, Err(Error::InvalidShape { .. })));
+
+        // Numeric headers, e.g. wavelengths, are valid column names.
+        let path = dir.path().join("wavelengths.csv");
+        std::fs::write(&path, "400,500,600\n0.1,0.2,0.3\n").unwrap();
+        assert_eq!(vec!["400", "500", "600"], read_csv_headers(&path).unwrap());
+    }
+
+    #[test]
+    fn test_serialize_with_precision_to_path() {
+        #[derive(Serialize)]
+        struct Row {
+            id: u64,
+            value: f64,
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let rows = [Row {
+            id: 12_345_678,
+            value: 0.1 + 0.2,
+        }];
+        let path = dir.path().join("rows.json");
+        serialize_json_with_precision_to_path(&rows, &path, 6).unwrap();
+        let json = std::fs::read_to_string(&path).unwrap();
+        assert!(json.contains("\"value\": 0.3\n"));
+        assert!(json.contains("\"id\": 12345678"));
+
+        let path = dir.path().join("rows.csv");
+        serialize_csv_rows_with_precision_to_path(&rows, &path, 6).unwrap();
+        assert_eq!(
+            "id,value\n12345678,0.3\n",
+            std::fs::read_to_string(&path).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_deserialize_csv_normalizing_delimiters_from_path() {
+        let path = "tests/data/mixed_delimiters.csv";
+        let rows: Vec<Vec<f64>> =
+            deserialize_csv_normalizing_delimiters_from_path(path, b",\t", false).unwrap();
+        assert_eq!(vec![vec![1., 2., 3.], vec![4., 5., 6.]], rows);
+        let e = deserialize_csv_rows_from_path::<Vec<f64>>(path);
+        assert!(e.is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("aligned.csv");
+        std::fs::write(&path, "x, y\t\"a,b\"\n1,\t2 \tc\n").unwrap();
+        let rows: Vec<Vec<String>> =
+            deserialize_csv_normalizing_delimiters_from_path(&path, b",\t ", true).unwrap();
+        assert_eq!(vec![vec!["1", "2", "c"]], rows);
+        let headers =
+            deserialize_csv_normalizing_delimiters_from_path::<Vec<String>>(&path, b",\t ", false);
+        assert!(matches!(headers, Err(Error::ParseCsv(_))));
+    }
+
+    #[test]
+    fn test_copy_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let dst = dir.path().join("example1.csv");
+        let copied = copy_file("tests/example1.csv", &dst).unwrap();
+        let expected = std::fs::read("tests/example1.csv").unwrap();
+        assert_eq!(expected.len() as u64, copied);
+        assert_eq!(expected, std::fs::read(&dst).unwrap());
+
+        // The destination is not overwritten, and the error names it.
+        let e = copy_file("tests/example2.csv", &dst).unwrap_err();
+        assert!(matches!(e, Error::FileIo(e) if *e.path == *dst));
+        assert_eq!(expected, std::fs::read(&dst).unwrap());
+
+        let e = copy_file(dir.path().join("missing.csv"), dir.path().join("out.csv"));
+        assert!(matches!(e, Err(Error::FileIo(e)) if e.path.ends_with("missing.csv")));
+    }
+
+    #[test]
+    fn test_deserialize_csv_with_preamble_from_path() {
+        let path = "tests/data/preamble.csv";
+        let (preamble, rows) =
+            deserialize_csv_with_preamble_from_path::<(f64, f64)>(path, 3).unwrap();
+        assert_eq!(
+            vec![
+                "Instrument: X-200",
+                "Date: 2024-03-01",
+                "Operator: A. Smith, lab 2"
+            ],
+            preamble
+        );
+        assert_eq!(vec![(0., 273.15), (1., 274.15)], rows);
+
+        let e = deserialize_csv_with_preamble_from_path::<(f64, f64)>(path, 7);
+        assert!(matches!(e, Err(Error::InvalidShape { .. })));
+        let e = deserialize_csv_with_preamble_from_path::<(f64, f64)>(path, usize::MAX);
+        assert!(matches!(e, Err(Error::InvalidShape { .. })));
+    }
+
+    #[test]
+    fn test_update_json_in_place() {
+        #[derive(Serialize, Deserialize)]
+        struct Counter {
+            name: String,
+            count: u32,
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("counter.json");
+        std::fs::write(&path, r#"{"name": "runs", "count": 1}"#).unwrap();
+        update_json_in_place(&path, |counter: &mut Counter| counter.count += 1).unwrap();
+        update_json_in_place(&path, |counter: &mut Counter| counter.count += 1).unwrap();
+        let counter: Counter = deserialize_json_from_path(&path).unwrap();
+        assert_eq!((String::from("runs"), 3), (counter.name, counter.count));
+        // Only the updated file remains.
+        assert_eq!(
+            vec![path.clone()],
+            collect_files_from_dir_path(dir.path()).unwrap()
+        );
+
+        let e = update_json_in_place(dir.path().join("missing.json"), |_: &mut Counter| {});
+        assert!(matches!(e, Err(Error::InvalidType { .. })));
+
+        // The temporary file is removed when the final rename fails, here because `path` becomes a non-empty directory.
+        let e = update_json_in_place(&path, |_: &mut Counter| {
+            std::fs::remove_file(&path).unwrap();
+            std::fs::create_dir(&path).unwrap();
+            std::fs::write(path.join("blocker"), "").unwrap();
+        });
+        assert!(matches!(e, Err(Error::FileIo(_))));
+        let temp_path = dir
+            .path()
+            .join(format!(".counter.json.tmp-{}", std::process::id()));
+        assert!(!temp_path.exists());
+    }
+
+    #[test]
+    fn test_deserialize_csv_indexed_from_path() {
+        let path = "tests/data/labelled.csv";
+        let table = deserialize_csv_indexed_from_path::<String, f64>(path, true).unwrap();
+        let expected = IndexedRows {
+            index_header: Some("station".to_string()),
+            index: vec!["north".to_string(), "south".to_string()],
+            rows: vec![vec![1.5, 2.], vec![3., 4.25]],
+        };
+        assert_eq!(expected, table);
+
+        let table = deserialize_csv_indexed_from_path::<String, f64>(path, false).unwrap();
+        assert_eq!(None, table.index_header);
+        let e = deserialize_csv_indexed_from_path::<u32, f64>(path, false);
+        assert!(matches!(e, Err(Error::ParseCsv(_))));
+    }
+
+    #[test]
+    fn test_skip_csv_comment_lines() {
+        let mut scan = std::io::Cursor::new(b"a\n# comment\n\nb\n".as_slice());
+        let mut scanned = 0;
+        assert_eq!(
+            2,
+            skip_csv_comment_lines(&mut scan, &mut scanned, 2).unwrap()
+        );
+        assert_eq!(13, scanned);
+        // Moving backwards is an error rather than an overflow.
+        assert!(skip_csv_comment_lines(&mut scan, &mut scanned, 2).is_err());
+    }
+
+    #[test]
+    fn test_output_file_new_with_parents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out/sub/file.jsonl");
+        let mut file = OutputFile::new_with_parents(&path).unwrap();
+        file.write_json_line(&[1, 2]).unwrap();
+        file.flush().unwrap();
+        assert!(path.is_file());
+
+        // The file itself must still be new.
+        assert!(OutputFile::new_with_parents(&path).is_err());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_output_file_new_with_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.jsonl");
+        let _ = OutputFile::new_with_mode(&path, 0o640).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(0o640, mode & 0o777);
+    }
+
+    /// Directories held in memory, for filesystem-free tests.
+    #[derive(Default)]
+    struct MemoryFileSystem {
+        dirs: std::cell::RefCell<std::collections::HashSet<PathBuf>>,
+    }
+
+    impl FileSystem for MemoryFileSystem {
+        fn create_dir_all(&self, path: &Path) -> std::io::Result<()> {
+            let mut dirs = self.dirs.borrow_mut();
+            dirs.extend(path.ancestors().map(Path::to_path_buf));
+            Ok(())
+        }
+
+        fn try_exists(&self, path: &Path) -> std::io::Result<bool> {
+            Ok(self.dirs.borrow().contains(path))
+        }
+
+        fn is_dir(&self, path: &Path) -> std::io::Result<bool> {
+            self.try_exists(path)
+        }
+    }
+
+    #[test]
+    fn test_create_incremented_directory_with_fs() {
+        let fs = MemoryFileSystem::default();
+        let base = Path::new("/memory/output");
+        fs.create_dir_all(&base.join("run_0")).unwrap();
+        fs.create_dir_all(&base.join("run_1")).unwrap();
+        let run = create_incremented_directory_with_fs(&fs, base).unwrap();
+        assert_eq!(base.join("run_2"), run);
+        assert!(fs.try_exists(&run).unwrap());
+
+        // Gaps are filled first.
+        let fs = MemoryFileSystem::default();
+        fs.create_dir_all(&base.join("run_0")).unwrap();
+        fs.create_dir_all(&base.join("run_2")).unwrap();
+        let run = create_incremented_directory_with_fs(&fs, base).unwrap();
+        assert_eq!(base.join("run_1"), run);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_create_incremented_directory_names_failed_run() {
+        let dir = tempfile::tempdir().unwrap();
+        // A dangling symlink doesn't "exist", but can't be created as a directory either.
+        std::os::unix::fs::symlink(dir.path().join("nowhere"), dir.path().join("run_0")).unwrap();
+
+        let e = create_incremented_directory(dir.path());
+        let Err(Error::FileIo(FileIoError { path, source })) = e else {
+            panic!("expected FileIo error, found {e:?}");
+        };
+        assert_eq!(dir.path().join("run_0").as_path(), &*path);
+        assert_eq!(std::io::ErrorKind::AlreadyExists, source.kind());
+    }
+
+    #[test]
+    fn test_create_incremented_directory_with_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let _ = create_incremented_directory(dir.path()).unwrap();
+        let run = create_incremented_directory_with_manifest(dir.path()).unwrap();
+        assert_eq!(dir.path().join("run_1"), run);
+
+        let manifest: RunManifest = deserialize_json_from_path(run.join("manifest.json")).unwrap();
+        assert_eq!(1, manifest.index);
+        assert_eq!(dir.path(), manifest.base_path);
+    }
+
+    #[test]
+    fn test_output_sink_from_writer() {
+        let mut sink = OutputSink::from_writer(vec![], "<memory>");
+        sink.write_json_line(&serde_json::json!({ "index": 0 }))
+            .unwrap();
+        sink.write_json_line(&serde_json::json!({ "index": 1 }))
+            .unwrap();
+        let result = String::from_utf8(sink.into_inner().unwrap()).unwrap();
+        assert_eq!("{\"index\":0}\n{\"index\":1}\n", result);
+    }
+
+    #[test]
+    fn test_shared_output_file() {
+        const THREADS: usize = 8;
+        const LINES: usize = 200;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shared.jsonl");
+        let file = SharedOutputFile::new(&path).unwrap();
+        std::thread::scope(|scope| {
+            for thread in 0..THREADS {
+                let file = &file;
+                let _ = scope.spawn(move || {
+                    for line in 0..LINES {
+                        let record = serde_json::json!({ "thread": thread, "line": line, "padding": "x".repeat(64) });
+                        file.write_json_line(&record).unwrap();
+                    }
+                });
+            }
+        });
+        file.flush().unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(THREADS * LINES, lines.len());
+    }
+
+    #[test]
+    fn test_rotating_output_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut file = RotatingOutputFile::new(dir.path().join("log.jsonl"), 32).unwrap();
+        // Each line is 12 bytes, so two fit in each file.
+        for i in 0..3 {
+            file.write_json_line(&serde_json::json!({ "index": i }))
+                .unwrap();
+        }
+        file.flush().unwrap();
+
+        let first = std::fs::read_to_string(dir.path().join("log.0.jsonl")).unwrap();
+        let second = std::fs::read_to_string(dir.path().join("log.1.jsonl")).unwrap();
+        assert_eq!("{\"index\":0}\n{\"index\":1}\n", first);
+        assert_eq!("{\"index\":2}\n", second);
+    }
+
+    #[test]
+    fn test_atomic_replace_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let outputs = dir.path().join("outputs");
+        atomic_replace_directory(&outputs, |tmp| {
+            std::fs::write(tmp.join("result.txt"), "first")?;
+            Ok(())
+        })
+        .unwrap();
+
+        // A failing build leaves the original intact, and cleans up after itself.
+        let e = atomic_replace_directory(&outputs, |tmp| {
+            std::fs::write(tmp.join("result.txt"), "partial")?;
+            Err(Error::Create {
+                path: tmp.into(),
+                msg: "build failed".to_string(),
+            })
+        });
+        assert!(matches!(e, Err(Error::Create { .. })));
+        let result = std::fs::read_to_string(outputs.join("result.txt")).unwrap();
+        assert_eq!("first", result);
+        assert_eq!(1, read_dir(dir.path()).unwrap().count());
+
+        atomic_replace_directory(&outputs, |tmp| {
+            std::fs::write(tmp.join("result.txt"), "second")?;
+            Ok(())
+        })
+        .unwrap();
+        let result = std::fs::read_to_string(outputs.join("result.txt")).unwrap();
+        assert_eq!("second", result);
+
+        // A failing rename also cleans up, here because a non-empty directory blocks moving the original aside.
+        let old_path = dir
+            .path()
+            .join(format!(".outputs.old-{}", std::process::id()));
+        let e = atomic_replace_directory(&outputs, |_| {
+            std::fs::create_dir(&old_path)?;
+            std::fs::write(old_path.join("blocker"), "")?;
+            Ok(())
+        });
+        assert!(matches!(e, Err(Error::FileIo(_))));
+        std::fs::remove_dir_all(&old_path).unwrap();
+        assert_eq!(1, read_dir(dir.path()).unwrap().count());
+        let result = std::fs::read_to_string(outputs.join("result.txt")).unwrap();
+        assert_eq!("second", result);
+    }
+
+    #[test]
+    fn test_dir_summary() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("a/b")).unwrap();
+        std::fs::write(dir.path().join("one.txt"), [0; 10]).unwrap();
+        std::fs::write(dir.path().join("a/two.txt"), [0; 20]).unwrap();
+        std::fs::write(dir.path().join("a/b/three.txt"), [0; 30]).unwrap();
+        // Symlinks are not followed.
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(dir.path().join("a"), dir.path().join("link")).unwrap();
+
+        let expected = DirSummary {
+            file_count: 3,
+            total_bytes: 60,
+            dir_count: 2,
+        };
+        assert_eq!(expected, dir_summary(dir.path()).unwrap());
+    }
+
+    #[test]
+    fn test_deserialize_csv_column_vectors_from_path_multiline() {
+        // Quoted fields may contain newlines, which must stay within a single cell.
+        let expected = vec![
+            vec!["1".to_string(), "2".to_string()],
+            vec!["first line\nsecond line".to_string(), "plain".to_string()],
+            vec!["a".to_string(), "b".to_string()],
+        ];
+        let result =
+            deserialize_csv_column_vectors_from_path::<String>("tests/data/multiline.csv").unwrap();
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_deserialize_csv_columns_with_quantiles_from_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("column.csv");
+        // 1 to 20, shuffled, with a NaN which is ignored.
+        let values = [
+            7, 20, 1, 13, 2, 19, 3, 18, 4, 17, 5, 16, 6, 15, 8, 14, 9, 12, 10, 11,
+        ];
+        let rows: Vec<String> = values.iter().map(ToString::to_string).collect();
+        std::fs::write(&path, format!("value\n{}\nNaN\n", rows.join("\n"))).unwrap();
+
+        let quantiles =
+            deserialize_csv_columns_with_quantiles_from_path(&path, &[0.5, 0.95]).unwrap();
+        assert_eq!(1, quantiles.len());
+        assert!((10.5 - quantiles[0][0]).abs() < 1e-12);
+        assert!((19.05 - quantiles[0][1]).abs() < 1e-12);
     }
 
     #[test]
@@ -0,0 +1,261 @@
+//! Labelled 2-dimensional grids of `f64` values.
+//!
+//! Save a grid alongside its axes, labels and units:
+//!     `grid.save_json(&"/path/to/grid.json");`
+//!
+//! Load it back, validating the dimensions:
+//!     `let grid = Grid2D::load_json(&"/path/to/grid.json");`
+//!
+//! Load a lookup table from a "heatmap" CSV, and interpolate within it:
+//!     `let value = GridInterpolator2D::from_csv(&"/path/to/table.csv")?.interpolate(x, y)?;`
+
+use crate::file_io::{
+    CsvReader, Error, FileIoError, deserialize_json_from_path, open_file, serialize_json_to_path,
+};
+use crate::interpolator::{InterpolationError, locate};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A 2-dimensional grid of values with labelled axes.
+///
+/// `values` is row-major: one row per `y_axis` entry, each with one value per `x_axis` entry.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default, Clone)]
+pub struct Grid2D {
+    pub x_axis: Vec<f64>,
+    pub y_axis: Vec<f64>,
+    pub values: Vec<Vec<f64>>,
+    pub x_label: String,
+    pub y_label: String,
+    pub value_units: String,
+}
+
+impl Grid2D {
+    /// Serialize the grid to a new JSON file at the provided path.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  creating `path` fails.
+    /// *  serialization fails.
+    pub fn save_json(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        serialize_json_to_path(self, path)
+    }
+
+    /// Deserialize a grid from the JSON file at the provided path, validating its dimensions.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  opening `path` fails.
+    /// *  deserialization fails.
+    /// *  the dimensions of `values` disagree with the axes.
+    pub fn load_json(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let grid: Self = deserialize_json_from_path(&path)?;
+        grid.check_shape().map_err(|msg| Error::InvalidShape {
+            path: path.as_ref().into(),
+            msg,
+        })?;
+
+        Ok(grid)
+    }
+
+    /// Checks that `values` has one row per `y_axis` entry and one column per `x_axis` entry.
+    fn check_shape(&self) -> Result<(), String> {
+        if self.values.len() != self.y_axis.len() {
+            return Err(format!(
+                "expected {} rows of values to match the y axis, found {}",
+                self.y_axis.len(),
+                self.values.len()
+            ));
+        }
+        if let Some((i, row)) = self
+            .values
+            .iter()
+            .enumerate()
+            .find(|(_, row)| row.len() != self.x_axis.len())
+        {
+            return Err(format!(
+                "expected {} values in row {i} to match the x axis, found {}",
+                self.x_axis.len(),
+                row.len()
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Bilinear interpolator over a rectangular grid of values.
+///
+/// `values` is row-major: one row per `y_axis` entry, each with one value per `x_axis` entry.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default, Clone)]
+pub struct GridInterpolator2D {
+    pub(crate) x_axis: Vec<f64>,
+    pub(crate) y_axis: Vec<f64>,
+    pub(crate) values: Vec<Vec<f64>>,
+}
+
+impl GridInterpolator2D {
+    /// Reads a grid from a "heatmap" CSV at the provided path.
+    ///
+    /// The first row holds the x axis, and the first column holds the y axis; the top left cell is ignored.
+    /// The remaining cells are the grid values. Both axes require at least two values, in increasing order.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  opening `path` fails.
+    /// *  the grid is not rectangular, or an axis has fewer than two values.
+    /// *  any cell (other than the top left) is not a number.
+    /// *  either axis is not strictly increasing.
+    pub fn from_csv(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let invalid_shape = |msg: String| Error::InvalidShape {
+            path: path.as_ref().into(),
+            msg,
+        };
+        let file = open_file(&path)?;
+        // Rows are checked for a consistent length here, for a clearer error.
+        let mut reader = CsvReader::builder()
+            .has_headers(false)
+            .flexible(true)
+            .build()
+            .csv_reader(file);
+
+        let mut x_axis = vec![];
+        let mut y_axis = vec![];
+        let mut values = vec![];
+        for (row, record) in reader.records().enumerate() {
+            let record = record.map_err(|source| FileIoError {
+                path: path.as_ref().into(),
+                source,
+            })?;
+            let cells = record
+                .iter()
+                .enumerate()
+                .skip(usize::from(row == 0))
+                .map(|(column, cell)| {
+                    cell.trim().parse::<f64>().map_err(|_| Error::InvalidValue {
+                        path: path.as_ref().into(),
+                        msg: format!(
+                            "expected a number at row {row} column {column}, found `{cell}`"
+                        ),
+                    })
+                })
+                .collect::<Result<Vec<f64>, Error>>()?;
+
+            if row == 0 {
+                x_axis = cells;
+            } else if cells.len() != x_axis.len() + 1 {
+                return Err(invalid_shape(format!(
+                    "expected {} cells in row {row}, found {}",
+                    x_axis.len() + 1,
+                    cells.len()
+                )));
+            } else {
+                y_axis.push(cells[0]);
+                values.push(cells[1..].to_vec());
+            }
+        }
+
+        if x_axis.len() < 2 || y_axis.len() < 2 {
+            return Err(invalid_shape(format!(
+                "expected at least a 2x2 grid, found {}x{}",
+                x_axis.len(),
+                y_axis.len()
+            )));
+        }
+        for (name, axis) in [("x", &x_axis), ("y", &y_axis)] {
+            if let Some(i) = axis.windows(2).position(|pair| pair[0] >= pair[1]) {
+                return Err(Error::InvalidValue {
+                    path: path.as_ref().into(),
+                    msg: format!(
+                        "expected a strictly increasing {name} axis, found {} at index {}",
+                        axis[i + 1],
+                        i + 1
+                    ),
+                });
+            }
+        }
+
+        Ok(Self {
+            x_axis,
+            y_axis,
+            values,
+        })
+    }
+
+    /// Provides the bilinearly interpolated value at `(x, y)`.
+    /// # Errors
+    ///
+    /// `OutOfBounds` and `NaN`, for either coordinate, and `InvalidKnots` if an axis has fewer than two values.
+    pub fn interpolate(&self, x: f64, y: f64) -> Result<f64, InterpolationError> {
+        let (i, dx) = locate(x, &self.x_axis)?;
+        let (j, dy) = locate(y, &self.y_axis)?;
+        let lower = (1. - dx) * self.values[j][i] + dx * self.values[j][i + 1];
+        let upper = (1. - dx) * self.values[j + 1][i] + dx * self.values[j + 1][i + 1];
+
+        Ok((1. - dy) * lower + dy * upper)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grid() -> Grid2D {
+        Grid2D {
+            x_axis: vec![0., 1., 2.],
+            y_axis: vec![10., 20.],
+            values: vec![vec![1., 2., 3.], vec![4., 5., 6.]],
+            x_label: "longitude".to_string(),
+            y_label: "latitude".to_string(),
+            value_units: "K".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_grid_json_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("grid.json");
+        let expected = grid();
+        expected.save_json(&path).unwrap();
+        assert_eq!(expected, Grid2D::load_json(&path).unwrap());
+    }
+
+    #[test]
+    fn test_grid_interpolator_from_csv() {
+        let grid = GridInterpolator2D::from_csv("tests/data/grid.csv").unwrap();
+        assert_eq!(vec![0., 1., 2.], grid.x_axis);
+        assert_eq!(vec![0., 1., 2.], grid.y_axis);
+        // Values are `10 * y + x`.
+        assert!((15.5 - grid.interpolate(0.5, 1.5).unwrap()).abs() < 1e-12);
+        assert!((22. - grid.interpolate(2., 2.).unwrap()).abs() < 1e-12);
+
+        let e = GridInterpolator2D::from_csv("tests/bad/ragged_grid.csv");
+        assert!(matches!(e, Err(Error::InvalidShape { .. })));
+        let e = GridInterpolator2D::from_csv("tests/bad/unsorted_grid.csv");
+        assert!(matches!(e, Err(Error::InvalidValue { msg, .. }) if msg.contains("x axis")));
+    }
+
+    #[test]
+    fn test_grid_interpolator_single_knot() {
+        let grid = GridInterpolator2D {
+            x_axis: vec![0.],
+            y_axis: vec![0., 1.],
+            values: vec![vec![1.], vec![2.]],
+        };
+        let e = grid.interpolate(0., 0.5);
+        assert!(matches!(e, Err(InterpolationError::InvalidKnots { .. })));
+    }
+
+    #[test]
+    fn test_grid_load_json_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("grid.json");
+        let mut grid = grid();
+        let _ = grid.values[1].pop();
+        grid.save_json(&path).unwrap();
+        let e = Grid2D::load_json(&path);
+        assert!(matches!(e, Err(Error::InvalidShape { .. })));
+    }
+}
@@ -1,6 +1,11 @@
 // Simple 1-dimensional and n-dimensional linear interpolators for f64.
 
+use crate::file_io::{
+    self, FileIoError, create_buffered_file_writer, deserialize_csv_rows_from_path, open_file,
+};
 use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::Path;
 use thiserror::Error;
 
 //TODO add a "tolerance" and "value" cache.
@@ -14,6 +19,59 @@ pub enum InterpolationError {
     OutOfBounds { x: f64, x_min: f64, x_max: f64 },
     #[error("attempted to interpolated NaN")]
     NaN,
+    #[error("unable to interpolate value: {x} lies within a gap in the data")]
+    InGap { x: f64 },
+    #[cfg(feature = "chrono")]
+    #[error(
+        "unable to interpolate time: {} expected within range {} and {}",
+        t.to_rfc3339(),
+        t_min.to_rfc3339(),
+        t_max.to_rfc3339()
+    )]
+    TimeOutOfBounds {
+        t: chrono::DateTime<chrono::Utc>,
+        t_min: chrono::DateTime<chrono::Utc>,
+        t_max: chrono::DateTime<chrono::Utc>,
+    },
+    #[error("mismatched lengths: expected {expected} values, found {found}")]
+    LengthMismatch { expected: usize, found: usize },
+    #[error("invalid knots: {msg}")]
+    InvalidKnots { msg: String },
+    #[error("unknown channel: {name}")]
+    UnknownChannel { name: String },
+    #[error("duplicate channel: {name}")]
+    DuplicateChannel { name: String },
+}
+
+/// Whether an interpolated value was limited by the range of the data.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Saturation {
+    /// Genuine interpolation within the data.
+    None,
+    /// Clamped to, or exactly at, the lower endpoint.
+    Low,
+    /// Clamped to, or exactly at, the upper endpoint.
+    High,
+}
+
+/// How `interpolate_bridging_nan` handles a query with no non-`NaN` knot on one side.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum BridgeMode {
+    /// Fail with `InGap`.
+    Error,
+    /// Use the value of the nearest non-`NaN` knot on the other side.
+    Clamp,
+}
+
+/// Common interface over interpolation strategies, so the method can be selected at runtime.
+///
+/// e.g. `let strategy: Box<dyn Interpolate> = Box::new(interpolator);`
+pub trait Interpolate {
+    /// Evaluates the interpolant at `x`.
+    /// # Errors
+    ///
+    /// `OutOfBounds` and `NaN`
+    fn eval(&self, x: f64) -> Result<f64, InterpolationError>;
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Default, Clone)]
@@ -26,6 +84,7 @@ where
 }
 
 impl<T: Clone + Default> Interpolator<T> {
+    #[must_use]
     pub fn new() -> Self {
         Self::default()
     }
@@ -34,10 +93,191 @@ impl<T: Clone + Default> Interpolator<T> {
         self.x_vals = x_vals.to_vec();
         self.y_vals = y_vals.to_vec();
     }
+
+    /// Returns true if `x` is within the range of the data (inclusive), and not `NaN`.
+    ///
+    /// Allows queries to be filtered cheaply instead of handling `OutOfBounds` errors.
+    #[must_use]
+    pub fn contains(&self, x: f64) -> bool {
+        !self.is_empty() && sanity_check(x, &self.x_vals).is_ok()
+    }
+
+    /// Returns the lowest `x` value, or `None` if empty.
+    #[must_use]
+    pub fn x_min(&self) -> Option<f64> {
+        self.x_vals.first().copied()
+    }
+
+    /// Returns the highest `x` value, or `None` if empty.
+    #[must_use]
+    pub fn x_max(&self) -> Option<f64> {
+        self.x_vals.last().copied()
+    }
+
+    /// Returns the number of knots.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.x_vals.len()
+    }
+
+    /// Returns true if there are no knots.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.x_vals.is_empty()
+    }
+
+    /// Checks the invariants shared by every interpolator: at least one knot, one `y` value per knot, and strictly
+    /// increasing `x` values without `NaN`.
+    fn check_knots(&self) -> Result<(), InterpolationError> {
+        if self.y_vals.len() != self.x_vals.len() {
+            return Err(InterpolationError::LengthMismatch {
+                expected: self.x_vals.len(),
+                found: self.y_vals.len(),
+            });
+        }
+        if self.x_vals.is_empty() {
+            return Err(InterpolationError::InvalidKnots {
+                msg: "expected at least one knot".to_string(),
+            });
+        }
+        if let Some(i) = self.x_vals.iter().position(|x| x.is_nan()) {
+            return Err(InterpolationError::InvalidKnots {
+                msg: format!("expected x values without NaN, found NaN at index {i}"),
+            });
+        }
+        if let Some(i) = self.x_vals.windows(2).position(|pair| pair[0] >= pair[1]) {
+            return Err(InterpolationError::InvalidKnots {
+                msg: format!(
+                    "expected strictly increasing x values, found {} at index {}",
+                    self.x_vals[i + 1],
+                    i + 1
+                ),
+            });
+        }
+
+        Ok(())
+    }
 }
 
 impl Interpolator<f64> {
     // 1-D Interpolator.
+    /// Checks that the interpolator is safe to query, e.g. after deserializing it from untrusted data.
+    /// # Errors
+    ///
+    /// `LengthMismatch` if there isn't one `y` value per knot, and `InvalidKnots` if there are no knots or the `x`
+    /// values are not strictly increasing (or include `NaN`).
+    pub fn validate(&self) -> Result<(), InterpolationError> {
+        self.check_knots()
+    }
+
+    /// Reads the knots from a two column CSV with headers `x,y`, as written by `to_csv`.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  opening `path` fails.
+    /// *  deserialization fails.
+    pub fn from_csv(path: impl AsRef<Path>) -> Result<Self, file_io::Error> {
+        let rows: Vec<(f64, f64)> = deserialize_csv_rows_from_path(path)?;
+        let (x_vals, y_vals) = rows.into_iter().unzip();
+
+        Ok(Interpolator { x_vals, y_vals })
+    }
+
+    /// Writes the knots to a new CSV, with headers `x,y` and one row per knot.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  `path` already exists, or creating it fails.
+    /// *  writing fails.
+    pub fn to_csv(&self, path: impl AsRef<Path>) -> Result<(), file_io::Error> {
+        let rows = self
+            .x_vals
+            .iter()
+            .zip(&self.y_vals)
+            .map(|(&x, &y)| vec![x, y]);
+        write_knots_csv(path, &["x".to_string(), "y".to_string()], rows)
+    }
+
+    /// Writes the knots to a new file in a compact binary format, much faster to load than JSON.
+    ///
+    /// The layout is the magic bytes `SFI1`, then the number of `x` values (`u64`) followed by the values (`f64`),
+    /// then likewise for the `y` values, all little-endian.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  `path` already exists, or creating it fails.
+    /// *  writing fails.
+    pub fn save_binary(&self, path: impl AsRef<Path>) -> Result<(), file_io::Error> {
+        let mut bytes = BINARY_MAGIC.to_vec();
+        for vals in [&self.x_vals, &self.y_vals] {
+            bytes.extend((vals.len() as u64).to_le_bytes());
+            bytes.extend(vals.iter().flat_map(|v| v.to_le_bytes()));
+        }
+
+        let mut writer = create_buffered_file_writer(&path)?;
+        writer
+            .write_all(&bytes)
+            .and_then(|()| writer.flush())
+            .map_err(|source| FileIoError {
+                path: path.as_ref().into(),
+                source,
+            })?;
+
+        Ok(())
+    }
+
+    /// Reads the knots from a file written by `save_binary`.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  opening or reading `path` fails.
+    /// *  the file is not in the binary format, is truncated or has trailing bytes.
+    /// *  the number of `x` and `y` values differ.
+    pub fn load_binary(path: impl AsRef<Path>) -> Result<Self, file_io::Error> {
+        let mut bytes = vec![];
+        let _ = open_file(&path)?
+            .read_to_end(&mut bytes)
+            .map_err(|source| FileIoError {
+                path: path.as_ref().into(),
+                source,
+            })?;
+        let invalid = |msg: &str| file_io::Error::InvalidValue {
+            path: path.as_ref().into(),
+            msg: msg.to_string(),
+        };
+
+        let mut rest = bytes
+            .strip_prefix(BINARY_MAGIC.as_slice())
+            .ok_or_else(|| invalid("not a binary interpolator file"))?;
+        let mut take_8 = || -> Option<[u8; 8]> {
+            let (head, tail) = rest.split_first_chunk::<8>()?;
+            rest = tail;
+            Some(*head)
+        };
+        let mut read_vals = || -> Option<Vec<f64>> {
+            let len = usize::try_from(u64::from_le_bytes(take_8()?)).ok()?;
+            (0..len).map(|_| take_8().map(f64::from_le_bytes)).collect()
+        };
+        let x_vals = read_vals().ok_or_else(|| invalid("truncated x values"))?;
+        let y_vals = read_vals().ok_or_else(|| invalid("truncated y values"))?;
+        if x_vals.len() != y_vals.len() {
+            return Err(invalid(&format!(
+                "expected {} y values, found {}",
+                x_vals.len(),
+                y_vals.len()
+            )));
+        }
+        if !rest.is_empty() {
+            return Err(invalid("unexpected trailing bytes"));
+        }
+
+        Ok(Interpolator { x_vals, y_vals })
+    }
+
     /// Provides the interpolated value
     /// # Errors
     ///
@@ -46,10 +286,585 @@ impl Interpolator<f64> {
         sanity_check(x, &self.x_vals)?;
         Ok(interpolate_1d(x, &self.x_vals, &self.y_vals))
     }
+
+    /// Provides the interpolated value, or `None` if `x` is out of range or `NaN`.
+    ///
+    /// Convenient in iterator chains, e.g. `xs.iter().filter_map(|&x| interp.interpolate_opt(x))`.
+    #[must_use]
+    pub fn interpolate_opt(&self, x: f64) -> Option<f64> {
+        self.interpolate(x).ok().map(|(_, y)| y)
+    }
+
+    /// Returns the lowest and highest `y` values, ignoring `NaN`, or `None` if there are none.
+    #[must_use]
+    pub fn y_range(&self) -> Option<(f64, f64)> {
+        self.y_vals
+            .iter()
+            .filter(|y| !y.is_nan())
+            .fold(None, |range, &y| match range {
+                None => Some((y, y)),
+                Some((min, max)) => Some((y.min(min), y.max(max))),
+            })
+    }
+
+    /// Provides the fractional position of `x` across the whole range of the data, from 0 at `x_min` to 1 at `x_max`.
+    /// # Errors
+    ///
+    /// `OutOfBounds` and `NaN`, and `InvalidKnots` if there are fewer than two knots.
+    pub fn normalized_position(&self, x: f64) -> Result<f64, InterpolationError> {
+        if self.x_vals.len() < 2 {
+            return Err(InterpolationError::InvalidKnots {
+                msg: "a normalized position requires at least two knots".to_string(),
+            });
+        }
+        sanity_check(x, &self.x_vals)?;
+        let x_min = self.x_vals[0];
+        let x_max = self.x_vals[self.x_vals.len() - 1];
+
+        Ok((x - x_min) / (x_max - x_min))
+    }
+
+    /// Provides the interpolated value at the fraction `t` of the whole range of the data, the inverse of
+    /// `normalized_position`.
+    ///
+    /// `t` of 0 is `x_min` and 1 is `x_max`, for sampling a curve evenly by parameter rather than by `x`.
+    /// # Errors
+    ///
+    /// `OutOfBounds` if `t` lies outside `[0, 1]`, `NaN`, and `InvalidKnots` if there are no knots.
+    pub fn interpolate_at_fraction(&self, t: f64) -> Result<f64, InterpolationError> {
+        sanity_check(t, &[0., 1.])?;
+        let (Some(x_min), Some(x_max)) = (self.x_min(), self.x_max()) else {
+            return Err(InterpolationError::InvalidKnots {
+                msg: "unable to interpolate an empty interpolator".to_string(),
+            });
+        };
+        // Exact at both endpoints, so `t` of 1 is never beyond `x_max`.
+        let (_, y) = self.interpolate((1. - t) * x_min + t * x_max)?;
+
+        Ok(y)
+    }
+
+    /// Provides the interpolated value together with the slope of the containing segment, from a single lookup.
+    ///
+    /// At an exact knot the slope of the right-hand segment is used, except at `x_max` where there is only the
+    /// left-hand segment.
+    /// # Errors
+    ///
+    /// `OutOfBounds` and `NaN`, and `InvalidKnots` if there are fewer than two knots.
+    pub fn interpolate_with_slope(&self, x: f64) -> Result<(f64, f64), InterpolationError> {
+        if self.x_vals.len() < 2 {
+            return Err(InterpolationError::InvalidKnots {
+                msg: "a slope requires at least two knots".to_string(),
+            });
+        }
+        let (i, dx) = locate(x, &self.x_vals)?;
+        let (y_lo, y_hi) = (self.y_vals[i], self.y_vals[i + 1]);
+        let slope = (y_hi - y_lo) / (self.x_vals[i + 1] - self.x_vals[i]);
+
+        Ok((y_lo + dx * (y_hi - y_lo), slope))
+    }
+
+    /// Provides the interpolated value, bridging over knots with `NaN` values, e.g. to salvage gappy data.
+    ///
+    /// The nearest knots with non-`NaN` values on each side of `x` are interpolated between, rather than the
+    /// bracketing knots. If one side has no such knot, `mode` decides between an error and clamping.
+    /// # Errors
+    ///
+    /// `OutOfBounds` and `NaN`, `InGap` if there are no non-`NaN` knots on one side and `mode` is
+    /// `BridgeMode::Error`, or on either side, and `InvalidKnots` if there are no knots.
+    pub fn interpolate_bridging_nan(
+        &self,
+        x: f64,
+        mode: BridgeMode,
+    ) -> Result<f64, InterpolationError> {
+        if self.is_empty() {
+            return Err(InterpolationError::InvalidKnots {
+                msg: "unable to interpolate an empty interpolator".to_string(),
+            });
+        }
+        sanity_check(x, &self.x_vals)?;
+        // Indices of the knots strictly below and above `x`, searched outwards.
+        let (below, above) = match self.x_vals.binary_search_by(|val| val.total_cmp(&x)) {
+            Ok(i) if !self.y_vals[i].is_nan() => return Ok(self.y_vals[i]),
+            Ok(i) => (i, i + 1),
+            Err(i) => (i, i),
+        };
+        let lo = (0..below).rev().find(|&i| !self.y_vals[i].is_nan());
+        let hi = (above..self.x_vals.len()).find(|&i| !self.y_vals[i].is_nan());
+
+        match (lo, hi, mode) {
+            (Some(lo), Some(hi), _) => {
+                let dx = (x - self.x_vals[lo]) / (self.x_vals[hi] - self.x_vals[lo]);
+                Ok(self.y_vals[lo] + dx * (self.y_vals[hi] - self.y_vals[lo]))
+            }
+            (Some(i), None, BridgeMode::Clamp) | (None, Some(i), BridgeMode::Clamp) => {
+                Ok(self.y_vals[i])
+            }
+            _ => Err(InterpolationError::InGap { x }),
+        }
+    }
+
+    /// Provides the maximum absolute difference between the values of two interpolators over `samples`.
+    ///
+    /// Useful for checking whether a regenerated lookup table has changed meaningfully.
+    /// # Errors
+    ///
+    /// `OutOfBounds` and `NaN` if any sample can't be interpolated by either interpolator.
+    pub fn max_abs_diff(&self, other: &Self, samples: &[f64]) -> Result<f64, InterpolationError> {
+        samples.iter().try_fold(0_f64, |max, &x| {
+            let (_, a) = self.interpolate(x)?;
+            let (_, b) = other.interpolate(x)?;
+            Ok(max.max((a - b).abs()))
+        })
+    }
+
+    /// Provides the knot closest to `x`, as `(index, x_knot, y_knot)`, without interpolating.
+    ///
+    /// When `x` is equidistant from two knots, the lower index is returned.
+    /// # Errors
+    ///
+    /// `OutOfBounds` and `NaN`, and `InvalidKnots` if there are no knots.
+    pub fn nearest_knot(&self, x: f64) -> Result<(usize, f64, f64), InterpolationError> {
+        if self.is_empty() {
+            return Err(InterpolationError::InvalidKnots {
+                msg: "unable to find the nearest knot of an empty interpolator".to_string(),
+            });
+        }
+        sanity_check(x, &self.x_vals)?;
+        let i = match self.x_vals.binary_search_by(|val| val.total_cmp(&x)) {
+            Ok(i) => i,
+            // x_vals[i - 1] < x < x_vals[i]
+            Err(i) => {
+                if x - self.x_vals[i - 1] <= self.x_vals[i] - x {
+                    i - 1
+                } else {
+                    i
+                }
+            }
+        };
+
+        Ok((i, self.x_vals[i], self.y_vals[i]))
+    }
+
+    /// Returns every `x` where the interpolant equals `target` (e.g. a temperature crossing freezing), ascending.
+    ///
+    /// Each segment straddling `target` contributes its linear crossing. Knots exactly at `target` are returned once,
+    /// and segments touching a `NaN` value are skipped.
+    #[must_use]
+    #[allow(clippy::float_cmp)]
+    pub fn crossings(&self, target: f64) -> Vec<f64> {
+        let mut out = vec![];
+        for (i, (&x, &y)) in self.x_vals.iter().zip(&self.y_vals).enumerate() {
+            if y == target {
+                out.push(x);
+            } else if let (Some(&next_x), Some(&next_y)) =
+                (self.x_vals.get(i + 1), self.y_vals.get(i + 1))
+            {
+                // Strictly either side of `target`, an exact hit on the next knot is found in its own turn.
+                if (y - target) * (next_y - target) < 0. {
+                    out.push(x + (target - y) / (next_y - y) * (next_x - x));
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Provides the interpolated value, never violating the overall monotonic direction of the data.
+    ///
+    /// The direction is that from the first to the last `y` value. For increasing data the result is at least every
+    /// knot value up to `x`, so small reversals become flat rather than decreasing (and vice versa for decreasing
+    /// data), e.g. for cumulative-distribution tables. Data with equal first and last values is not clamped.
+    /// # Errors
+    ///
+    /// `OutOfBounds` and `NaN`, and `InvalidKnots` if there are no knots.
+    pub fn interpolate_monotone_clamped(&self, x: f64) -> Result<f64, InterpolationError> {
+        if self.is_empty() {
+            return Err(InterpolationError::InvalidKnots {
+                msg: "unable to interpolate an empty interpolator".to_string(),
+            });
+        }
+        let (_, y) = self.interpolate(x)?;
+        let first = self.y_vals[0];
+        let last = self.y_vals[self.y_vals.len() - 1];
+        // Knots at or before `x`.
+        let seen = self.y_vals[..self.x_vals.partition_point(|&k| k <= x)].iter();
+
+        Ok(if last > first {
+            seen.fold(y, |y, &knot| y.max(knot))
+        } else if last < first {
+            seen.fold(y, |y, &knot| y.min(knot))
+        } else {
+            y
+        })
+    }
+
+    /// Simplifies to at most `max_knots` knots while preserving the shape of the curve, e.g. to shrink a dense table.
+    ///
+    /// Douglas-Peucker style: starting from the endpoints, which are always kept, the knot furthest (vertically)
+    /// from the current simplification is repeatedly added back. Stops early once the remaining knots lie exactly on
+    /// the simplification.
+    /// # Errors
+    ///
+    /// `InvalidKnots` if `max_knots < 2`.
+    pub fn decimate(&self, max_knots: usize) -> Result<Interpolator<f64>, InterpolationError> {
+        if max_knots < 2 {
+            return Err(InterpolationError::InvalidKnots {
+                msg: format!(
+                    "expected at least two knots to keep the endpoints, found {max_knots}"
+                ),
+            });
+        }
+        if self.x_vals.len() <= max_knots {
+            return Ok(self.clone());
+        }
+
+        // Indices of the kept knots, in increasing order.
+        let mut kept = vec![0, self.x_vals.len() - 1];
+        while kept.len() < max_knots {
+            // The knot deviating most from the segment of kept knots containing it, and its insertion position.
+            let mut furthest: Option<(f64, usize, usize)> = None;
+            for (pos, pair) in kept.windows(2).enumerate() {
+                let (lo, hi) = (pair[0], pair[1]);
+                let slope =
+                    (self.y_vals[hi] - self.y_vals[lo]) / (self.x_vals[hi] - self.x_vals[lo]);
+                for i in lo + 1..hi {
+                    let line = self.y_vals[lo] + slope * (self.x_vals[i] - self.x_vals[lo]);
+                    let deviation = (self.y_vals[i] - line).abs();
+                    if deviation > furthest.map_or(0., |(max, _, _)| max) {
+                        furthest = Some((deviation, pos + 1, i));
+                    }
+                }
+            }
+            let Some((_, pos, i)) = furthest else {
+                break;
+            };
+            kept.insert(pos, i);
+        }
+
+        Ok(Interpolator {
+            x_vals: kept.iter().map(|&i| self.x_vals[i]).collect(),
+            y_vals: kept.iter().map(|&i| self.y_vals[i]).collect(),
+        })
+    }
+
+    /// Resamples onto `n` evenly spaced knots across `[x_min, x_max]`, e.g. before an FFT or for plotting.
+    /// # Errors
+    ///
+    /// `InvalidKnots` if `n < 2` or there are no knots to resample.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn resample_uniform(&self, n: usize) -> Result<Interpolator<f64>, InterpolationError> {
+        let (Some(x_min), Some(x_max)) = (self.x_min(), self.x_max()) else {
+            return Err(InterpolationError::InvalidKnots {
+                msg: "unable to resample an empty interpolator".to_string(),
+            });
+        };
+        if n < 2 {
+            return Err(InterpolationError::InvalidKnots {
+                msg: format!("expected at least two resampled knots, found {n}"),
+            });
+        }
+
+        let step = (x_max - x_min) / (n - 1) as f64;
+        // The last knot is exactly `x_max`, free of rounding in `step`.
+        let x_vals: Vec<f64> = (0..n)
+            .map(|i| {
+                if i == n - 1 {
+                    x_max
+                } else {
+                    x_min + step * i as f64
+                }
+            })
+            .collect();
+        let y_vals = x_vals
+            .iter()
+            .map(|&x| self.interpolate(x).map(|(_, y)| y))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Interpolator { x_vals, y_vals })
+    }
+
+    /// Returns the length of the interpolated curve between `a` and `b`, in either order.
+    ///
+    /// Sums `sqrt(dx^2 + dy^2)` over the segments between the bounds, including partial end segments.
+    /// # Errors
+    ///
+    /// `OutOfBounds` and `NaN` for either bound.
+    pub fn arc_length(&self, a: f64, b: f64) -> Result<f64, InterpolationError> {
+        let (_, y_a) = self.interpolate(a)?;
+        let (_, y_b) = self.interpolate(b)?;
+        let ((lo, y_lo), (hi, y_hi)) = if a <= b {
+            ((a, y_a), (b, y_b))
+        } else {
+            ((b, y_b), (a, y_a))
+        };
+
+        // Knots strictly between the bounds.
+        let start = self.x_vals.partition_point(|&x| x <= lo);
+        let end = self.x_vals.partition_point(|&x| x < hi);
+        let inner = self.x_vals[start..end.max(start)]
+            .iter()
+            .zip(&self.y_vals[start..end.max(start)]);
+
+        let mut prev = (lo, y_lo);
+        let mut length = 0.;
+        for point in inner.map(|(&x, &y)| (x, y)).chain([(hi, y_hi)]) {
+            length += (point.0 - prev.0).hypot(point.1 - prev.1);
+            prev = point;
+        }
+
+        Ok(length)
+    }
+
+    /// Provides the interpolated value rounded to the nearest integer, with ties to even, e.g. for classifications.
+    ///
+    /// Values beyond the range of `i64` saturate.
+    /// # Errors
+    ///
+    /// `OutOfBounds`, and `NaN` if either `x` or the interpolated value is `NaN`.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn interpolate_rounded(&self, x: f64) -> Result<i64, InterpolationError> {
+        let (_, y) = self.interpolate(x)?;
+        if y.is_nan() {
+            return Err(InterpolationError::NaN);
+        }
+
+        Ok(y.round_ties_even() as i64)
+    }
+
+    /// Provides the interpolated value, clamped to the range of the data. This variant never fails.
+    ///
+    /// Out of range `x` is clamped to the nearest endpoint, and `NaN` returns the value at the lower endpoint.
+    /// An empty interpolator returns `NaN`. Intended for real-time loops where a value is always required.
+    #[must_use]
+    pub fn interpolate_clamped(&self, x: f64) -> f64 {
+        let (Some(x_min), Some(x_max)) = (self.x_min(), self.x_max()) else {
+            return f64::NAN;
+        };
+        let x = if x.is_nan() {
+            x_min
+        } else {
+            x.clamp(x_min, x_max)
+        };
+
+        interpolate_1d(x, &self.x_vals, &self.y_vals).1
+    }
+
+    /// Provides the clamped interpolated value (as `interpolate_clamped`), and whether it is saturated at an endpoint.
+    ///
+    /// An empty interpolator returns `NaN`, unsaturated.
+    #[must_use]
+    pub fn interpolate_saturating(&self, x: f64) -> (f64, Saturation) {
+        let (Some(x_min), Some(x_max)) = (self.x_min(), self.x_max()) else {
+            return (f64::NAN, Saturation::None);
+        };
+        let saturation = if x.is_nan() || x <= x_min {
+            Saturation::Low
+        } else if x >= x_max {
+            Saturation::High
+        } else {
+            Saturation::None
+        };
+
+        (self.interpolate_clamped(x), saturation)
+    }
+
+    /// Provides the interpolated value, treating `NaN` knots in `y_vals` as gaps in the data.
+    ///
+    /// Any segment bounded by a `NaN` knot is undefined, rather than silently producing `NaN`.
+    /// # Errors
+    ///
+    /// `OutOfBounds`, `NaN` and `InGap`, and `InvalidKnots` if there are no knots.
+    pub fn interpolate_with_gaps(&self, x: f64) -> Result<(f64, f64), InterpolationError> {
+        if self.is_empty() {
+            return Err(InterpolationError::InvalidKnots {
+                msg: "unable to interpolate an empty interpolator".to_string(),
+            });
+        }
+        sanity_check(x, &self.x_vals)?;
+        let in_gap = match self.x_vals.binary_search_by(|val| val.total_cmp(&x)) {
+            Ok(i) => self.y_vals[i].is_nan(),
+            Err(i) => self.y_vals[i - 1].is_nan() || self.y_vals[i].is_nan(),
+        };
+        if in_gap {
+            return Err(InterpolationError::InGap { x });
+        }
+
+        Ok(interpolate_1d(x, &self.x_vals, &self.y_vals))
+    }
+}
+
+// Piecewise linear strategy.
+impl Interpolate for Interpolator<f64> {
+    fn eval(&self, x: f64) -> Result<f64, InterpolationError> {
+        let (_, y) = self.interpolate(x)?;
+        Ok(y)
+    }
 }
 
 impl Interpolator<Vec<f64>> {
     // n-D Interpolator.
+    /// Checks that the interpolator is safe to query, e.g. after deserializing it from untrusted data.
+    /// # Errors
+    ///
+    /// `LengthMismatch` if there isn't one `y` value per knot, or the knots have differing numbers of channels, and
+    /// `InvalidKnots` if there are no knots or the `x` values are not strictly increasing (or include `NaN`).
+    pub fn validate(&self) -> Result<(), InterpolationError> {
+        self.check_knots()?;
+        let channels = self.y_vals[0].len();
+        if let Some(y) = self.y_vals.iter().find(|y| y.len() != channels) {
+            return Err(InterpolationError::LengthMismatch {
+                expected: channels,
+                found: y.len(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Reads the knots from a CSV with `x` in the first column and one column per channel, as written by `to_csv`.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  opening `path` fails.
+    /// *  deserialization fails.
+    pub fn from_csv(path: impl AsRef<Path>) -> Result<Self, file_io::Error> {
+        let rows: Vec<Vec<f64>> = deserialize_csv_rows_from_path(path)?;
+        let (x_vals, y_vals) = rows
+            .into_iter()
+            .map(|mut row| {
+                let y = row.split_off(1);
+                (row[0], y)
+            })
+            .unzip();
+
+        Ok(Interpolator { x_vals, y_vals })
+    }
+
+    /// Reads the knots from separate files: the x axis from the single column CSV at `x_path`, and one channel of
+    /// y values from each CSV (".csv") file in `y_dir`, in sorted filename order.
+    ///
+    /// Each value file holds a single column with one value per x knot, e.g. a field at one timestep.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  opening `x_path`, `y_dir` or any of the value files fails.
+    /// *  any file has other than a single column, or a value file's length differs from the x axis.
+    /// *  deserialization fails.
+    pub fn from_separate_csv(
+        x_path: impl AsRef<Path>,
+        y_dir: impl AsRef<Path>,
+    ) -> Result<Self, file_io::Error> {
+        let x_vals = read_single_column(x_path.as_ref())?;
+
+        let mut files = file_io::collect_files_from_dir_path(&y_dir)?;
+        files.retain(|file| file.extension().is_some_and(|ext| ext == "csv"));
+        files.sort();
+
+        let mut y_vals = vec![Vec::with_capacity(files.len()); x_vals.len()];
+        for file in files {
+            let values = read_single_column(&file)?;
+            if values.len() != x_vals.len() {
+                return Err(file_io::Error::InvalidShape {
+                    path: file.into(),
+                    msg: format!(
+                        "expected {} values to match the x axis, found {}",
+                        x_vals.len(),
+                        values.len()
+                    ),
+                });
+            }
+            for (y, value) in y_vals.iter_mut().zip(values) {
+                y.push(value);
+            }
+        }
+
+        Ok(Interpolator { x_vals, y_vals })
+    }
+
+    /// Combines 1-D interpolators sharing an x axis into one, with a channel per interpolator, for joint queries.
+    /// # Errors
+    ///
+    /// `InvalidKnots` if `interps` is empty, or their `x` values are not identical.
+    /// `LengthMismatch` if any interpolator has a different number of `y` values than `x` values.
+    pub fn from_scalar_interpolators(
+        interps: &[Interpolator<f64>],
+    ) -> Result<Self, InterpolationError> {
+        let Some(first) = interps.first() else {
+            return Err(InterpolationError::InvalidKnots {
+                msg: "expected at least one interpolator".to_string(),
+            });
+        };
+        if let Some(i) = interps
+            .iter()
+            .position(|interp| interp.x_vals != first.x_vals)
+        {
+            return Err(InterpolationError::InvalidKnots {
+                msg: format!("x values of interpolator {i} differ from interpolator 0"),
+            });
+        }
+        if let Some(interp) = interps
+            .iter()
+            .find(|interp| interp.y_vals.len() != interp.x_vals.len())
+        {
+            return Err(InterpolationError::LengthMismatch {
+                expected: interp.x_vals.len(),
+                found: interp.y_vals.len(),
+            });
+        }
+        let y_vals = (0..first.x_vals.len())
+            .map(|knot| interps.iter().map(|interp| interp.y_vals[knot]).collect())
+            .collect();
+
+        Ok(Interpolator {
+            x_vals: first.x_vals.clone(),
+            y_vals,
+        })
+    }
+
+    /// Splits into one 1-D interpolator per channel, the inverse of `from_scalar_interpolators`.
+    /// # Errors
+    ///
+    /// `LengthMismatch` if the knots have differing numbers of channels.
+    pub fn split_channels(&self) -> Result<Vec<Interpolator<f64>>, InterpolationError> {
+        let channels = self.y_vals.first().map_or(0, Vec::len);
+        if let Some(y) = self.y_vals.iter().find(|y| y.len() != channels) {
+            return Err(InterpolationError::LengthMismatch {
+                expected: channels,
+                found: y.len(),
+            });
+        }
+
+        Ok((0..channels)
+            .map(|channel| Interpolator {
+                x_vals: self.x_vals.clone(),
+                y_vals: self.y_vals.iter().map(|y| y[channel]).collect(),
+            })
+            .collect())
+    }
+
+    /// Writes the knots to a new CSV, with headers `x,y0,y1,...` and one row per knot.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  `path` already exists, or creating it fails.
+    /// *  writing fails.
+    pub fn to_csv(&self, path: impl AsRef<Path>) -> Result<(), file_io::Error> {
+        let channels = self.y_vals.first().map_or(0, Vec::len);
+        let headers: Vec<String> = std::iter::once("x".to_string())
+            .chain((0..channels).map(|i| format!("y{i}")))
+            .collect();
+        let rows = self.x_vals.iter().zip(&self.y_vals).map(|(&x, y)| {
+            let mut row = vec![x];
+            row.extend(y);
+            row
+        });
+        write_knots_csv(path, &headers, rows)
+    }
+
     /// Provides the interpolated value, or an error if out of range.
     /// # Errors
     ///
@@ -58,6 +873,99 @@ impl Interpolator<Vec<f64>> {
         sanity_check(x, &self.x_vals)?;
         Ok(interpolate(x, &self.x_vals, &self.y_vals))
     }
+
+    /// Provides the interpolated values for many queries in one pass.
+    ///
+    /// Intended for sorted `xs`: a single cursor walks the knots instead of a fresh binary search per query.
+    /// Unsorted queries are still handled correctly, but the cursor restarts whenever a query moves backwards.
+    /// Results are identical to repeated calls to `interpolate`.
+    /// # Errors
+    ///
+    /// `OutOfBounds` and `NaN` for the first offending query.
+    pub fn interpolate_many(&self, xs: &[f64]) -> Result<Vec<(f64, Vec<f64>)>, InterpolationError> {
+        let mut out = Vec::with_capacity(xs.len());
+        // Index of the first knot not less than the previous query.
+        let mut cursor = 0;
+
+        for &x in xs {
+            sanity_check(x, &self.x_vals)?;
+            out.push(self.interpolate_from_cursor(x, &mut cursor));
+        }
+
+        Ok(out)
+    }
+
+    /// Provides the interpolated values for many unsorted queries, in the original order.
+    ///
+    /// The queries are sorted first, so the cursor of `interpolate_many` walks the knots only once,
+    /// and the results are then scattered back to their original positions.
+    /// Each query gets its own result, so an offending query doesn't prevent the others.
+    #[must_use]
+    pub fn interpolate_many_unsorted(
+        &self,
+        xs: &[f64],
+    ) -> Vec<Result<(f64, Vec<f64>), InterpolationError>> {
+        let mut order: Vec<usize> = (0..xs.len()).collect();
+        order.sort_by(|&a, &b| xs[a].total_cmp(&xs[b]));
+
+        // Placeholders, every slot is overwritten as `order` is a permutation.
+        let mut out: Vec<_> = xs.iter().map(|_| Err(InterpolationError::NaN)).collect();
+        let mut cursor = 0;
+        for i in order {
+            let x = xs[i];
+            out[i] = sanity_check(x, &self.x_vals)
+                .map(|()| self.interpolate_from_cursor(x, &mut cursor));
+        }
+
+        out
+    }
+
+    /// Interpolate an in-range `x`, advancing `cursor` (the first knot not less than the previous query).
+    fn interpolate_from_cursor(&self, x: f64, cursor: &mut usize) -> (f64, Vec<f64>) {
+        // Query moved backwards, restart the walk.
+        if *cursor > 0 && x.total_cmp(&self.x_vals[*cursor - 1]).is_le() {
+            *cursor = 0;
+        }
+        // `x <= x_max` so the cursor never runs past the last knot.
+        while self.x_vals[*cursor].total_cmp(&x).is_lt() {
+            *cursor += 1;
+        }
+
+        let i = *cursor;
+        if self.x_vals[i].total_cmp(&x).is_eq() {
+            // Exact match found: x_vals[i] == x
+            (self.x_vals[i], self.y_vals[i].clone())
+        } else {
+            // x_vals[i - 1] < x < x_vals[i]
+            let prev_x = self.x_vals[i - 1];
+            let next_x = self.x_vals[i];
+            let delta = (x - prev_x) / (next_x - prev_x);
+
+            (next_x, blend(delta, &self.y_vals[i - 1], &self.y_vals[i]))
+        }
+    }
+}
+
+/// Weighted average of several interpolators at `x`, e.g. for ensemble blending.
+///
+/// Each entry is `(weight, interpolator)`, and the result is normalized by the total weight.
+/// A total weight of zero produces `NaN`.
+/// # Errors
+///
+/// `OutOfBounds` and `NaN` from the first interpolator that fails.
+pub fn interpolate_weighted(
+    interps: &[(f64, &Interpolator<f64>)],
+    x: f64,
+) -> Result<f64, InterpolationError> {
+    let mut sum = 0.;
+    let mut total_weight = 0.;
+    for (weight, interpolator) in interps {
+        let (_, y) = interpolator.interpolate(x)?;
+        sum += weight * y;
+        total_weight += weight;
+    }
+
+    Ok(sum / total_weight)
 }
 
 fn sanity_check(x: f64, x_vals: &[f64]) -> Result<(), InterpolationError> {
@@ -75,6 +983,22 @@ fn sanity_check(x: f64, x_vals: &[f64]) -> Result<(), InterpolationError> {
     Ok(())
 }
 
+// Locates the segment containing `x`, returning the index of its lower knot and the fractional position within it.
+// Requires at least two knots. An exact match on the last knot is the end of the final segment.
+pub(crate) fn locate(x: f64, x_vals: &[f64]) -> Result<(usize, f64), InterpolationError> {
+    if x_vals.len() < 2 {
+        return Err(InterpolationError::InvalidKnots {
+            msg: format!("expected at least two knots, found {}", x_vals.len()),
+        });
+    }
+    sanity_check(x, x_vals)?;
+    match x_vals.binary_search_by(|val| val.total_cmp(&x)) {
+        Ok(i) if i + 1 < x_vals.len() => Ok((i, 0.)),
+        Ok(i) => Ok((i - 1, 1.)),
+        Err(i) => Ok((i - 1, (x - x_vals[i - 1]) / (x_vals[i] - x_vals[i - 1]))),
+    }
+}
+
 // Interpolation for 1-dimensional array.
 fn interpolate_1d(x: f64, x_vals: &[f64], y_vals: &[f64]) -> (f64, f64) {
     match x_vals.binary_search_by(|val| val.total_cmp(&x)) {
@@ -106,18 +1030,76 @@ fn interpolate(x: f64, x_vals: &[f64], y_vals: &[Vec<f64>]) -> (f64, Vec<f64>) {
             let prev_x = x_vals[i - 1];
             let next_x = x_vals[i];
             let delta = (x - prev_x) / (next_x - prev_x);
-            let prev_y = &y_vals[i - 1];
-            let next_y = &y_vals[i];
-            let y = prev_y
-                .iter()
-                .zip(next_y.iter())
-                .map(|(prev, next)| (1. - delta) * prev + delta * next)
-                .collect();
+            let y = blend(delta, &y_vals[i - 1], &y_vals[i]);
 
             (next_x, y)
         }
     }
 }
 
+// Leading bytes of the `save_binary` format, including its version.
+const BINARY_MAGIC: [u8; 4] = *b"SFI1";
+
+// Reads the values of a CSV file with a single column.
+fn read_single_column(path: &Path) -> Result<Vec<f64>, file_io::Error> {
+    let mut columns = file_io::deserialize_csv_column_vectors_from_path::<f64>(path)?;
+    if columns.len() != 1 {
+        return Err(file_io::Error::InvalidShape {
+            path: path.into(),
+            msg: format!("expected a single column, found {}", columns.len()),
+        });
+    }
+
+    Ok(columns.remove(0))
+}
+
+// Writes the `headers` and then `rows` to a new CSV file.
+fn write_knots_csv(
+    path: impl AsRef<Path>,
+    headers: &[String],
+    rows: impl Iterator<Item = Vec<f64>>,
+) -> Result<(), file_io::Error> {
+    let map_err = |source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    };
+    let mut writer = csv::Writer::from_writer(create_buffered_file_writer(&path)?);
+    writer.write_record(headers).map_err(map_err)?;
+    for row in rows {
+        writer.serialize(row).map_err(map_err)?;
+    }
+    writer.flush().map_err(|source| FileIoError {
+        path: path.as_ref().into(),
+        source,
+    })?;
+
+    Ok(())
+}
+
+// Linear blend of two n-dimensional knots, `delta` is the fractional position within the segment.
+fn blend(delta: f64, prev_y: &[f64], next_y: &[f64]) -> Vec<f64> {
+    prev_y
+        .iter()
+        .zip(next_y.iter())
+        .map(|(prev, next)| (1. - delta) * prev + delta * next)
+        .collect()
+}
+
+mod checkpoint;
+pub use checkpoint::CheckpointingInterpolatorBuilder;
+mod memoized;
+pub use memoized::MemoizedInterpolator;
+mod uncertain;
+pub use uncertain::UncertainInterpolator;
+mod hermite;
+pub use hermite::HermiteInterpolator;
+mod named;
+pub use named::NamedInterpolator;
+
+#[cfg(feature = "chrono")]
+mod time;
+#[cfg(feature = "chrono")]
+pub use time::TimeInterpolator;
+
 #[cfg(test)]
 mod tests;
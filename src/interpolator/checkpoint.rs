@@ -0,0 +1,113 @@
+// Incremental building of an interpolator, with periodic JSON checkpoints.
+
+use super::Interpolator;
+use crate::file_io::{self, FileIoError, deserialize_json_from_path, serialize_json_to_path};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Builds an interpolator from streamed knots, saving a JSON checkpoint every `interval` pushes.
+///
+/// After a crash, `resume` continues from the last checkpoint, so only the knots pushed since are lost.
+#[derive(Debug)]
+pub struct CheckpointingInterpolatorBuilder<T: Default> {
+    interpolator: Interpolator<T>,
+    checkpoint_path: PathBuf,
+    interval: usize,
+    pending: usize,
+}
+
+impl<T> CheckpointingInterpolatorBuilder<T>
+where
+    T: Serialize + for<'a> Deserialize<'a> + Clone + Default,
+{
+    /// Starts an empty build, checkpointing to `checkpoint_path` every `interval` pushes (0 disables them).
+    #[must_use]
+    pub fn new(checkpoint_path: impl AsRef<Path>, interval: usize) -> Self {
+        Self {
+            interpolator: Interpolator::new(),
+            checkpoint_path: checkpoint_path.as_ref().to_path_buf(),
+            interval,
+            pending: 0,
+        }
+    }
+
+    /// Continues a build from the checkpoint at `checkpoint_path`, or starts an empty one if there is none yet.
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// *  opening the existing checkpoint fails.
+    /// *  deserialization fails.
+    pub fn resume(
+        checkpoint_path: impl AsRef<Path>,
+        interval: usize,
+    ) -> Result<Self, file_io::Error> {
+        let mut builder = Self::new(checkpoint_path, interval);
+        if builder.checkpoint_path.exists() {
+            builder.interpolator = deserialize_json_from_path(&builder.checkpoint_path)?;
+        }
+
+        Ok(builder)
+    }
+
+    /// Returns the number of knots pushed so far, including those restored by `resume`.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.interpolator.len()
+    }
+
+    /// Returns true if no knots have been pushed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.interpolator.is_empty()
+    }
+
+    /// Appends a knot, which must lie beyond the previous one, checkpointing if the interval is reached.
+    /// # Errors
+    ///
+    /// Returns an error if writing the checkpoint fails.
+    pub fn push(&mut self, x: f64, y: T) -> Result<(), file_io::Error> {
+        self.interpolator.x_vals.push(x);
+        self.interpolator.y_vals.push(y);
+        self.pending += 1;
+        if self.interval > 0 && self.pending >= self.interval {
+            self.checkpoint()?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the knots pushed so far to the checkpoint file, replacing the previous checkpoint.
+    ///
+    /// The checkpoint is written to a temporary sibling first, so a crash mid-write leaves the prior one intact.
+    /// # Errors
+    ///
+    /// Returns an error if writing or renaming the checkpoint fails.
+    pub fn checkpoint(&mut self) -> Result<(), file_io::Error> {
+        let mut temp_name = self.checkpoint_path.as_os_str().to_owned();
+        temp_name.push(".tmp");
+        let temp_path = PathBuf::from(temp_name);
+
+        // A leftover from an interrupted checkpoint is stale.
+        if temp_path.exists() {
+            std::fs::remove_file(&temp_path).map_err(|source| FileIoError {
+                path: temp_path.as_path().into(),
+                source,
+            })?;
+        }
+        serialize_json_to_path(&self.interpolator, &temp_path)?;
+        std::fs::rename(&temp_path, &self.checkpoint_path).map_err(|source| FileIoError {
+            path: self.checkpoint_path.as_path().into(),
+            source,
+        })?;
+        self.pending = 0;
+
+        Ok(())
+    }
+
+    /// Finishes the build, returning the interpolator. The checkpoint file is left in place.
+    #[must_use]
+    pub fn finish(self) -> Interpolator<T> {
+        self.interpolator
+    }
+}
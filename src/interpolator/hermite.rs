@@ -0,0 +1,109 @@
+// Cubic Hermite interpolator with known tangents.
+
+use super::{Interpolate, InterpolationError, locate};
+use std::cmp::Ordering;
+
+/// 1-D cubic Hermite interpolator, honoring a tangent (slope) at each knot.
+///
+/// Gives more control than a linear interpolant when slopes are known a priori, e.g. physically constrained curves.
+#[derive(PartialEq, Debug, Default, Clone)]
+pub struct HermiteInterpolator {
+    x_vals: Vec<f64>,
+    y_vals: Vec<f64>,
+    tangents: Vec<f64>,
+}
+
+impl HermiteInterpolator {
+    /// Creates an interpolator from strictly increasing `x_vals`, with the value and tangent at each knot.
+    /// # Errors
+    ///
+    /// `LengthMismatch` if `y_vals` or `tangents` don't have one value per knot,
+    /// and `InvalidKnots` if there are fewer than two knots or they are not strictly increasing.
+    pub fn new(
+        x_vals: &[f64],
+        y_vals: &[f64],
+        tangents: &[f64],
+    ) -> Result<Self, InterpolationError> {
+        for found in [y_vals.len(), tangents.len()] {
+            if found != x_vals.len() {
+                return Err(InterpolationError::LengthMismatch {
+                    expected: x_vals.len(),
+                    found,
+                });
+            }
+        }
+        if x_vals.len() < 2 {
+            return Err(InterpolationError::InvalidKnots {
+                msg: format!("expected at least two knots, found {}", x_vals.len()),
+            });
+        }
+        if let Some(i) = x_vals
+            .windows(2)
+            .position(|pair| pair[0].partial_cmp(&pair[1]) != Some(Ordering::Less))
+        {
+            return Err(InterpolationError::InvalidKnots {
+                msg: format!(
+                    "expected strictly increasing x values, found {} at index {}",
+                    x_vals[i + 1],
+                    i + 1
+                ),
+            });
+        }
+
+        Ok(Self {
+            x_vals: x_vals.to_vec(),
+            y_vals: y_vals.to_vec(),
+            tangents: tangents.to_vec(),
+        })
+    }
+
+    /// Creates an interpolator with the provided slopes at the endpoints, and Catmull-Rom tangents in between.
+    ///
+    /// Each interior tangent is the slope between its two neighbouring knots.
+    /// # Errors
+    ///
+    /// As `new`.
+    pub fn with_end_slopes(
+        x_vals: &[f64],
+        y_vals: &[f64],
+        start_slope: f64,
+        end_slope: f64,
+    ) -> Result<Self, InterpolationError> {
+        if y_vals.len() != x_vals.len() {
+            return Err(InterpolationError::LengthMismatch {
+                expected: x_vals.len(),
+                found: y_vals.len(),
+            });
+        }
+        let mut tangents = vec![start_slope; x_vals.len()];
+        for i in 1..x_vals.len().saturating_sub(1) {
+            tangents[i] = (y_vals[i + 1] - y_vals[i - 1]) / (x_vals[i + 1] - x_vals[i - 1]);
+        }
+        if let Some(last) = tangents.last_mut() {
+            *last = end_slope;
+        }
+
+        Self::new(x_vals, y_vals, &tangents)
+    }
+
+    /// Provides the interpolated value, evaluating the cubic Hermite basis on the segment containing `x`.
+    /// # Errors
+    ///
+    /// `OutOfBounds` and `NaN`
+    pub fn interpolate(&self, x: f64) -> Result<f64, InterpolationError> {
+        let (i, t) = locate(x, &self.x_vals)?;
+        let h = self.x_vals[i + 1] - self.x_vals[i];
+        let (t2, t3) = (t * t, t * t * t);
+
+        Ok((2. * t3 - 3. * t2 + 1.) * self.y_vals[i]
+            + (t3 - 2. * t2 + t) * h * self.tangents[i]
+            + (-2. * t3 + 3. * t2) * self.y_vals[i + 1]
+            + (t3 - t2) * h * self.tangents[i + 1])
+    }
+}
+
+impl Interpolate for HermiteInterpolator {
+    fn eval(&self, x: f64) -> Result<f64, InterpolationError> {
+        self.interpolate(x)
+    }
+}
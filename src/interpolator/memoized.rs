@@ -0,0 +1,68 @@
+// 1-D interpolator memoizing results for repeated identical queries.
+
+use super::{InterpolationError, Interpolator};
+use std::collections::HashMap;
+
+/// 1-D linear interpolator caching each result by its exact query `x`.
+///
+/// Trades memory for speed when a small set of queries repeats, e.g. a fixed set of standard pressure levels.
+/// The cache grows with every distinct query, so call `clear_cache` if the query set changes.
+#[derive(Debug, Default, Clone)]
+pub struct MemoizedInterpolator {
+    interpolator: Interpolator<f64>,
+    // Interpolated values keyed by `x.to_bits()`.
+    cache: HashMap<u64, f64>,
+    hits: usize,
+}
+
+impl MemoizedInterpolator {
+    /// Wraps an existing interpolator, with an empty cache.
+    #[must_use]
+    pub fn new(interpolator: Interpolator<f64>) -> Self {
+        Self {
+            interpolator,
+            ..Self::default()
+        }
+    }
+
+    /// Replaces the knots, clearing the cache as the cached values no longer apply.
+    pub fn init(&mut self, x_vals: &[f64], y_vals: &[f64]) {
+        self.interpolator.init(x_vals, y_vals);
+        self.clear_cache();
+    }
+
+    /// Provides the interpolated value, from the cache if `x` has been queried before.
+    ///
+    /// Failed queries are not cached.
+    /// # Errors
+    ///
+    /// `OutOfBounds` and `NaN`
+    pub fn interpolate_memoized(&mut self, x: f64) -> Result<f64, InterpolationError> {
+        if let Some(&y) = self.cache.get(&x.to_bits()) {
+            self.hits += 1;
+            return Ok(y);
+        }
+        let (_, y) = self.interpolator.interpolate(x)?;
+        let _ = self.cache.insert(x.to_bits(), y);
+
+        Ok(y)
+    }
+
+    /// Empties the cache and resets the hit counter.
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
+        self.hits = 0;
+    }
+
+    /// Returns the number of queries served from the cache since it was last cleared.
+    #[must_use]
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Returns the wrapped interpolator.
+    #[must_use]
+    pub fn interpolator(&self) -> &Interpolator<f64> {
+        &self.interpolator
+    }
+}
@@ -0,0 +1,74 @@
+// n-D interpolator with named channels.
+
+use super::{InterpolationError, Interpolator};
+use std::collections::HashMap;
+
+/// n-D linear interpolator whose channels are named, e.g. temperature, pressure and humidity.
+#[derive(PartialEq, Debug, Default, Clone)]
+pub struct NamedInterpolator {
+    interpolator: Interpolator<Vec<f64>>,
+    names: Vec<String>,
+}
+
+impl NamedInterpolator {
+    /// Names the channels of an n-D interpolator, in order.
+    /// # Errors
+    ///
+    /// `LengthMismatch` if any knot doesn't have one value per name, and `DuplicateChannel` if a name is repeated.
+    pub fn new(
+        interpolator: Interpolator<Vec<f64>>,
+        names: &[&str],
+    ) -> Result<Self, InterpolationError> {
+        if let Some(y) = interpolator.y_vals.iter().find(|y| y.len() != names.len()) {
+            return Err(InterpolationError::LengthMismatch {
+                expected: names.len(),
+                found: y.len(),
+            });
+        }
+        if let Some(name) = names
+            .iter()
+            .enumerate()
+            .find_map(|(i, name)| names[..i].contains(name).then_some(name))
+        {
+            return Err(InterpolationError::DuplicateChannel {
+                name: (*name).to_string(),
+            });
+        }
+
+        Ok(Self {
+            interpolator,
+            names: names.iter().map(ToString::to_string).collect(),
+        })
+    }
+
+    /// Returns the channel names, in order.
+    #[must_use]
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Provides the interpolated value of every channel, by name.
+    /// # Errors
+    ///
+    /// `OutOfBounds` and `NaN`
+    pub fn interpolate(&self, x: f64) -> Result<HashMap<String, f64>, InterpolationError> {
+        let (_, y) = self.interpolator.interpolate(x)?;
+
+        Ok(self.names.iter().cloned().zip(y).collect())
+    }
+
+    /// Provides the interpolated value of the channel called `name`.
+    /// # Errors
+    ///
+    /// `UnknownChannel`, `OutOfBounds` and `NaN`
+    pub fn interpolate_channel(&self, x: f64, name: &str) -> Result<f64, InterpolationError> {
+        let channel = self.names.iter().position(|n| n == name).ok_or_else(|| {
+            InterpolationError::UnknownChannel {
+                name: name.to_string(),
+            }
+        })?;
+        let (_, y) = self.interpolator.interpolate(x)?;
+
+        Ok(y[channel])
+    }
+}
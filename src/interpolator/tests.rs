@@ -1,3 +1,5 @@
+#![allow(clippy::float_cmp)]
+
 use super::*;
 
 #[test]
@@ -29,8 +31,7 @@ fn _interpolate_nd() {
 }
 
 #[test]
-#[should_panic]
-// TODO match on the specific error type.
+#[should_panic(expected = "OutOfBounds")]
 fn _interpolate_1d_too_small() {
     let interpolator = Interpolator {
         x_vals: vec![1., 2., 3., 4., 5.],
@@ -41,8 +42,7 @@ fn _interpolate_1d_too_small() {
 }
 
 #[test]
-#[should_panic]
-// TODO match on the specific error type.
+#[should_panic(expected = "OutOfBounds")]
 fn _interpolate_1d_too_big() {
     let interpolator = Interpolator {
         x_vals: vec![1., 2., 3., 4., 5.],
@@ -53,8 +53,7 @@ fn _interpolate_1d_too_big() {
 }
 
 #[test]
-#[should_panic]
-// TODO match on the specific error type.
+#[should_panic(expected = "NaN")]
 fn _interpolate_1d_nan() {
     let interpolator = Interpolator {
         x_vals: vec![1., 2., 3., 4., 5.],
@@ -63,3 +62,815 @@ fn _interpolate_1d_nan() {
     let x = f64::NAN;
     let _result = interpolator.interpolate(x).unwrap();
 }
+
+#[test]
+fn _interpolate_dyn_linear() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![2., 4., 6., 8., 10.],
+    };
+    let x = 2.5;
+    let (_, expected) = interpolator.interpolate(x).unwrap();
+    let strategy: Box<dyn Interpolate> = Box::new(interpolator);
+    let result = strategy.eval(x).unwrap();
+    assert_eq!(expected, result);
+}
+
+#[test]
+fn _interpolate_many_nd() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![vec![2., 4., 6., 8., 10.], vec![3., 5., 7., 9., 11.], vec![
+            1., 2., 3., 4., 5.,
+        ]],
+    };
+    let xs = [1., 1.5, 2., 2.5, 2.7, 3.];
+    let expected: Vec<_> = xs
+        .iter()
+        .map(|&x| interpolator.interpolate(x).unwrap())
+        .collect();
+    let result = interpolator.interpolate_many(&xs).unwrap();
+    assert_eq!(expected, result);
+}
+
+#[test]
+fn _interpolate_many_unsorted() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![
+            vec![2., 3.],
+            vec![4., 5.],
+            vec![6., 7.],
+            vec![8., 9.],
+            vec![10., 11.],
+        ],
+    };
+    let xs = [4.5, 1., 6., 2.5, f64::NAN, 5., 1.5, 3.];
+    let results = interpolator.interpolate_many_unsorted(&xs);
+    assert_eq!(xs.len(), results.len());
+    for (&x, result) in xs.iter().zip(results) {
+        match interpolator.interpolate(x) {
+            Ok(expected) => assert_eq!(expected, result.unwrap()),
+            Err(_) => assert!(result.is_err()),
+        }
+    }
+}
+
+#[test]
+fn _interpolate_with_gaps() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![2., 4., f64::NAN, 8., 10.],
+    };
+    let result = interpolator.interpolate_with_gaps(2.5);
+    assert!(matches!(result, Err(InterpolationError::InGap { x }) if x == 2.5));
+
+    let result = interpolator.interpolate_with_gaps(4.5).unwrap();
+    assert_eq!((5., 9.), result);
+
+    let result = Interpolator::new().interpolate_with_gaps(1.);
+    assert!(matches!(
+        result,
+        Err(InterpolationError::InvalidKnots { .. })
+    ));
+}
+
+#[test]
+fn _csv_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![2., 4.1, 6., 8.25, 10.],
+    };
+    let path = dir.path().join("knots.csv");
+    interpolator.to_csv(&path).unwrap();
+    assert_eq!(interpolator, Interpolator::<f64>::from_csv(&path).unwrap());
+
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3.],
+        y_vals: vec![vec![2., 3.], vec![4.5, 5.], vec![6., 7.1]],
+    };
+    let path = dir.path().join("knots_nd.csv");
+    interpolator.to_csv(&path).unwrap();
+    assert!(
+        std::fs::read_to_string(&path)
+            .unwrap()
+            .starts_with("x,y0,y1\n")
+    );
+    assert_eq!(
+        interpolator,
+        Interpolator::<Vec<f64>>::from_csv(&path).unwrap()
+    );
+}
+
+#[test]
+fn _checkpointing_builder_resume() {
+    let dir = tempfile::tempdir().unwrap();
+    let knots: Vec<(f64, f64)> = (0..10).map(|i| (f64::from(i), f64::from(i * i))).collect();
+
+    let mut builder = CheckpointingInterpolatorBuilder::new(dir.path().join("full.json"), 3);
+    for &(x, y) in &knots {
+        builder.push(x, y).unwrap();
+    }
+    let expected = builder.finish();
+
+    // Interrupted after 7 pushes, so only the checkpoint at 6 survives.
+    let path = dir.path().join("partial.json");
+    let mut builder = CheckpointingInterpolatorBuilder::new(&path, 3);
+    for &(x, y) in &knots[..7] {
+        builder.push(x, y).unwrap();
+    }
+    drop(builder);
+
+    let mut builder = CheckpointingInterpolatorBuilder::<f64>::resume(&path, 3).unwrap();
+    assert_eq!(6, builder.len());
+    for &(x, y) in &knots[builder.len()..] {
+        builder.push(x, y).unwrap();
+    }
+    assert_eq!(expected, builder.finish());
+}
+
+#[test]
+fn _crossings() {
+    let interpolator = Interpolator {
+        x_vals: vec![0., 1., 2., 3., 4., 5., 6.],
+        y_vals: vec![-2., 2., -2., 0., 4., -4., f64::NAN],
+    };
+    assert_eq!(vec![0.5, 1.5, 3., 4.5], interpolator.crossings(0.));
+    assert_eq!(Vec::<f64>::new(), interpolator.crossings(10.));
+}
+
+#[test]
+fn _interpolate_memoized() {
+    let mut interpolator = MemoizedInterpolator::new(Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![2., 4., 6., 8., 10.],
+    });
+    assert_eq!(5., interpolator.interpolate_memoized(2.5).unwrap());
+    assert_eq!(0, interpolator.hits());
+    assert_eq!(5., interpolator.interpolate_memoized(2.5).unwrap());
+    assert_eq!(1, interpolator.hits());
+    assert_eq!(7., interpolator.interpolate_memoized(3.5).unwrap());
+    assert_eq!(1, interpolator.hits());
+
+    // New knots invalidate the cache.
+    interpolator.init(&[1., 5.], &[0., 4.]);
+    assert_eq!(0, interpolator.hits());
+    assert_eq!(1.5, interpolator.interpolate_memoized(2.5).unwrap());
+    assert_eq!(0, interpolator.hits());
+}
+
+#[test]
+fn _interpolate_with_uncertainty() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![2., 4., 6., 8., 10.],
+    };
+    let y_err = [0.1, 0.2, 0.3, 0.4, 0.5];
+    let uncertain = UncertainInterpolator::new(interpolator.clone(), &y_err).unwrap();
+
+    let (y, y_err) = uncertain.interpolate(2.5).unwrap();
+    assert_eq!(5., y);
+    assert!((y_err - (0.25 * 0.04 + 0.25 * 0.09_f64).sqrt()).abs() < 1e-12);
+    assert_eq!((8., 0.4), uncertain.interpolate(4.).unwrap());
+
+    let e = UncertainInterpolator::new(interpolator, &[0.1]);
+    assert!(matches!(
+        e,
+        Err(InterpolationError::LengthMismatch {
+            expected: 5,
+            found: 1
+        })
+    ));
+}
+
+#[test]
+fn _hermite_cubic() {
+    // A cubic Hermite interpolant with exact slopes reproduces a cubic exactly.
+    let f = |x: f64| x.powi(3) - 2. * x;
+    let df = |x: f64| 3. * x.powi(2) - 2.;
+
+    let x_vals = [0., 2.];
+    let y_vals = x_vals.map(f);
+    let hermite = HermiteInterpolator::with_end_slopes(&x_vals, &y_vals, df(0.), df(2.)).unwrap();
+    for x in [0., 0.5, 1., 1.5, 2.] {
+        assert!((f(x) - hermite.interpolate(x).unwrap()).abs() < 1e-12);
+    }
+
+    let x_vals = [-1., 0., 0.5, 2.];
+    let hermite = HermiteInterpolator::new(&x_vals, &x_vals.map(f), &x_vals.map(df)).unwrap();
+    for x in [-0.75, 0.25, 1.2] {
+        assert!((f(x) - hermite.eval(x).unwrap()).abs() < 1e-12);
+    }
+    assert!(matches!(
+        hermite.interpolate(3.),
+        Err(InterpolationError::OutOfBounds { .. })
+    ));
+
+    let e = HermiteInterpolator::new(&[0., 1.], &[0., 1.], &[0.]);
+    assert!(matches!(e, Err(InterpolationError::LengthMismatch { .. })));
+    let e = HermiteInterpolator::new(&[1., 0.], &[0., 1.], &[0., 0.]);
+    assert!(matches!(e, Err(InterpolationError::InvalidKnots { .. })));
+}
+
+#[test]
+fn _interpolate_monotone_clamped() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![0., 0.5, 0.45, 0.8, 1.],
+    };
+    let xs: Vec<f64> = (0..=40).map(|i| 1. + f64::from(i) * 0.1).collect();
+    let ys: Vec<f64> = xs
+        .iter()
+        .map(|&x| interpolator.interpolate_monotone_clamped(x).unwrap())
+        .collect();
+    assert!(ys.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert_eq!(0.5, interpolator.interpolate_monotone_clamped(2.5).unwrap());
+    assert_eq!(
+        0.25,
+        interpolator.interpolate_monotone_clamped(1.5).unwrap()
+    );
+
+    // Decreasing data.
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3.],
+        y_vals: vec![1., 0.4, 0.],
+    };
+    let reversed = Interpolator {
+        x_vals: vec![1., 2., 3., 4.],
+        y_vals: vec![1., 0.4, 0.5, 0.],
+    };
+    assert_eq!(0.7, interpolator.interpolate_monotone_clamped(1.5).unwrap());
+    assert_eq!(0.4, reversed.interpolate_monotone_clamped(2.5).unwrap());
+
+    assert!(matches!(
+        Interpolator::new().interpolate_monotone_clamped(1.),
+        Err(InterpolationError::InvalidKnots { .. })
+    ));
+}
+
+#[test]
+fn _resample_uniform() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 4., 5.],
+        y_vals: vec![2., 4., 8., 10.],
+    };
+    let resampled = interpolator.resample_uniform(9).unwrap();
+    assert_eq!(9, resampled.len());
+    assert_eq!(Some(1.), resampled.x_min());
+    assert_eq!(Some(5.), resampled.x_max());
+    for (x, y) in resampled.x_vals.iter().zip(&resampled.y_vals) {
+        assert!((2. * x - y).abs() < 1e-12);
+    }
+    assert!((resampled.x_vals[1] - 1.5).abs() < 1e-12);
+
+    assert!(matches!(
+        interpolator.resample_uniform(1),
+        Err(InterpolationError::InvalidKnots { .. })
+    ));
+}
+
+#[test]
+fn _binary_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let x_vals: Vec<f64> = (0..1000).map(f64::from).collect();
+    let y_vals: Vec<f64> = x_vals.iter().map(|x| x.sqrt() / 3.).collect();
+    let interpolator = Interpolator { x_vals, y_vals };
+
+    let binary = dir.path().join("knots.bin");
+    interpolator.save_binary(&binary).unwrap();
+    assert_eq!(interpolator, Interpolator::load_binary(&binary).unwrap());
+
+    // Truncated files are rejected.
+    let bytes = std::fs::read(&binary).unwrap();
+    let truncated = dir.path().join("truncated.bin");
+    std::fs::write(&truncated, &bytes[..bytes.len() - 4]).unwrap();
+    assert!(matches!(
+        Interpolator::load_binary(&truncated),
+        Err(crate::Error::InvalidValue { .. })
+    ));
+
+    // So are files with trailing bytes.
+    let trailing = dir.path().join("trailing.bin");
+    std::fs::write(&trailing, [bytes.as_slice(), &[0; 8]].concat()).unwrap();
+    assert!(matches!(
+        Interpolator::load_binary(&trailing),
+        Err(crate::Error::InvalidValue { .. })
+    ));
+
+    // And files with mismatched x and y lengths.
+    let mismatched = dir.path().join("mismatched.bin");
+    Interpolator {
+        x_vals: vec![0., 1., 2.],
+        y_vals: vec![0., 1.],
+    }
+    .save_binary(&mismatched)
+    .unwrap();
+    assert!(matches!(
+        Interpolator::load_binary(&mismatched),
+        Err(crate::Error::InvalidValue { .. })
+    ));
+}
+
+#[test]
+fn _named_interpolator() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3.],
+        y_vals: vec![
+            vec![10., 1000., 0.5],
+            vec![20., 1010., 0.6],
+            vec![30., 1020., 0.7],
+        ],
+    };
+    let named = NamedInterpolator::new(
+        interpolator.clone(),
+        &["temperature", "pressure", "humidity"],
+    )
+    .unwrap();
+    assert_eq!(1005., named.interpolate_channel(1.5, "pressure").unwrap());
+    assert_eq!(25., named.interpolate(2.5).unwrap()["temperature"]);
+    assert!(matches!(
+        named.interpolate_channel(1.5, "wind"),
+        Err(InterpolationError::UnknownChannel { .. })
+    ));
+
+    let e = NamedInterpolator::new(interpolator.clone(), &["temperature", "pressure"]);
+    assert!(matches!(e, Err(InterpolationError::LengthMismatch { .. })));
+
+    let e = NamedInterpolator::new(interpolator, &["temperature", "pressure", "temperature"]);
+    assert!(
+        matches!(e, Err(InterpolationError::DuplicateChannel { name }) if name == "temperature")
+    );
+}
+
+#[test]
+fn _arc_length() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![2., 4., 6., 8., 10.],
+    };
+    let unit = 5_f64.sqrt();
+    assert!((4. * unit - interpolator.arc_length(1., 5.).unwrap()).abs() < 1e-12);
+    assert!((1.5 * unit - interpolator.arc_length(1.5, 3.).unwrap()).abs() < 1e-12);
+    assert!((0.5 * unit - interpolator.arc_length(4.5, 4.).unwrap()).abs() < 1e-12);
+    assert_eq!(0., interpolator.arc_length(2., 2.).unwrap());
+    assert!(matches!(
+        interpolator.arc_length(0., 2.),
+        Err(InterpolationError::OutOfBounds { .. })
+    ));
+}
+
+#[test]
+fn _interpolate_rounded() {
+    let interpolator = Interpolator {
+        x_vals: vec![0., 1., 2., 3.],
+        y_vals: vec![2., 3., 4., f64::NAN],
+    };
+    // Ties go to the even integer.
+    assert_eq!(2, interpolator.interpolate_rounded(0.5).unwrap());
+    assert_eq!(4, interpolator.interpolate_rounded(1.5).unwrap());
+    assert_eq!(3, interpolator.interpolate_rounded(0.6).unwrap());
+    assert!(matches!(
+        interpolator.interpolate_rounded(2.5),
+        Err(InterpolationError::NaN)
+    ));
+}
+
+#[test]
+fn _interpolate_at_fraction() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 4., 5.],
+        y_vals: vec![2., 3., 7., 1.],
+    };
+    assert_eq!(
+        interpolator.interpolate(3.).unwrap().1,
+        interpolator.interpolate_at_fraction(0.5).unwrap()
+    );
+    assert_eq!(2., interpolator.interpolate_at_fraction(0.).unwrap());
+    assert_eq!(1., interpolator.interpolate_at_fraction(1.).unwrap());
+    assert!(matches!(
+        interpolator.interpolate_at_fraction(1.5),
+        Err(InterpolationError::OutOfBounds { .. })
+    ));
+    assert!(matches!(
+        interpolator.interpolate_at_fraction(f64::NAN),
+        Err(InterpolationError::NaN)
+    ));
+    assert!(matches!(
+        Interpolator::<f64>::new().interpolate_at_fraction(0.5),
+        Err(InterpolationError::InvalidKnots { .. })
+    ));
+}
+
+#[test]
+fn _from_separate_csv() {
+    let interpolator = Interpolator::<Vec<f64>>::from_separate_csv(
+        "tests/data/separate/grid.csv",
+        "tests/data/separate/values",
+    )
+    .unwrap();
+    let expected = Interpolator {
+        x_vals: vec![0., 1., 2.],
+        y_vals: vec![vec![1., 10.], vec![2., 20.], vec![3., 30.]],
+    };
+    assert_eq!(expected, interpolator);
+
+    let e = Interpolator::<Vec<f64>>::from_separate_csv(
+        "tests/data/single_column.csv",
+        "tests/data/separate/values",
+    );
+    assert!(matches!(e, Err(file_io::Error::InvalidShape { .. })));
+}
+
+#[test]
+fn _interpolate_with_slope() {
+    let interpolator = Interpolator {
+        x_vals: vec![0., 1., 2.5, 4.],
+        y_vals: vec![0., 2., 5., 8.],
+    };
+    for x in [0., 0.5, 1., 2., 2.5, 3.75, 4.] {
+        assert_eq!(
+            (2. * x, 2.),
+            interpolator.interpolate_with_slope(x).unwrap()
+        );
+    }
+
+    // The right-hand segment at a knot, and the left-hand segment at the end.
+    let interpolator = Interpolator {
+        x_vals: vec![0., 1., 2.],
+        y_vals: vec![0., 1., 3.],
+    };
+    assert_eq!((1., 2.), interpolator.interpolate_with_slope(1.).unwrap());
+    assert_eq!((3., 2.), interpolator.interpolate_with_slope(2.).unwrap());
+    assert!(matches!(
+        interpolator.interpolate_with_slope(2.5),
+        Err(InterpolationError::OutOfBounds { .. })
+    ));
+}
+
+#[test]
+fn _interpolate_bridging_nan() {
+    let interpolator = Interpolator {
+        x_vals: vec![0., 1., 2., 3.],
+        y_vals: vec![0., f64::NAN, 4., f64::NAN],
+    };
+    assert_eq!(
+        1.,
+        interpolator
+            .interpolate_bridging_nan(0.5, BridgeMode::Error)
+            .unwrap()
+    );
+    assert_eq!(
+        2.,
+        interpolator
+            .interpolate_bridging_nan(1., BridgeMode::Error)
+            .unwrap()
+    );
+    assert_eq!(
+        4.,
+        interpolator
+            .interpolate_bridging_nan(2., BridgeMode::Error)
+            .unwrap()
+    );
+
+    // No non-`NaN` knot above.
+    assert!(matches!(
+        interpolator.interpolate_bridging_nan(2.5, BridgeMode::Error),
+        Err(InterpolationError::InGap { .. })
+    ));
+    assert_eq!(
+        4.,
+        interpolator
+            .interpolate_bridging_nan(2.5, BridgeMode::Clamp)
+            .unwrap()
+    );
+    assert!(matches!(
+        interpolator.interpolate_bridging_nan(3.5, BridgeMode::Clamp),
+        Err(InterpolationError::OutOfBounds { .. })
+    ));
+    assert!(matches!(
+        Interpolator::<f64>::new().interpolate_bridging_nan(0.5, BridgeMode::Clamp),
+        Err(InterpolationError::InvalidKnots { .. })
+    ));
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn _decimate() {
+    // Nearly linear, with a small wobble.
+    let x_vals: Vec<f64> = (0..100).map(|i| f64::from(i) / 10.).collect();
+    let y_vals = x_vals
+        .iter()
+        .enumerate()
+        .map(|(i, x)| 2. * x + 1e-6 * (i % 3) as f64)
+        .collect();
+    let interpolator = Interpolator { x_vals, y_vals };
+    let decimated = interpolator.decimate(2).unwrap();
+    assert_eq!(vec![0., 9.9], decimated.x_vals);
+    assert_eq!(interpolator.y_vals[0], decimated.y_vals[0]);
+    assert_eq!(interpolator.y_vals[99], decimated.y_vals[1]);
+
+    // The peak is kept first, and the exactly linear remainder is dropped.
+    let interpolator = Interpolator {
+        x_vals: vec![0., 1., 2., 3., 4.],
+        y_vals: vec![0., 1., 2., 1., 0.],
+    };
+    let decimated = interpolator.decimate(4).unwrap();
+    assert_eq!(vec![0., 2., 4.], decimated.x_vals);
+    assert!(matches!(
+        interpolator.decimate(1),
+        Err(InterpolationError::InvalidKnots { .. })
+    ));
+}
+
+#[test]
+fn _from_scalar_interpolators() {
+    let a = Interpolator {
+        x_vals: vec![0., 1., 2.],
+        y_vals: vec![0., 2., 4.],
+    };
+    let b = Interpolator {
+        x_vals: vec![0., 1., 2.],
+        y_vals: vec![10., 0., 10.],
+    };
+    let combined =
+        Interpolator::<Vec<f64>>::from_scalar_interpolators(&[a.clone(), b.clone()]).unwrap();
+    assert_eq!(vec![1., 5.], combined.interpolate(0.5).unwrap().1);
+    assert_eq!(vec![a.clone(), b], combined.split_channels().unwrap());
+
+    let ragged = Interpolator {
+        x_vals: vec![0., 1.],
+        y_vals: vec![vec![0., 1.], vec![2.]],
+    };
+    assert!(matches!(
+        ragged.split_channels(),
+        Err(InterpolationError::LengthMismatch {
+            expected: 2,
+            found: 1
+        })
+    ));
+
+    let shifted = Interpolator {
+        x_vals: vec![0., 1.5, 2.],
+        y_vals: vec![0., 2., 4.],
+    };
+    assert!(matches!(
+        Interpolator::<Vec<f64>>::from_scalar_interpolators(&[a.clone(), shifted]),
+        Err(InterpolationError::InvalidKnots { .. })
+    ));
+
+    let short = Interpolator {
+        x_vals: vec![0., 1., 2.],
+        y_vals: vec![0., 2.],
+    };
+    assert!(matches!(
+        Interpolator::<Vec<f64>>::from_scalar_interpolators(&[a, short]),
+        Err(InterpolationError::LengthMismatch {
+            expected: 3,
+            found: 2
+        })
+    ));
+}
+
+#[test]
+fn _validate() {
+    let interpolator: Interpolator<f64> =
+        serde_json::from_str(r#"{"x_vals": [0.0, 1.0, 2.0], "y_vals": [1.0, 2.0, 3.0]}"#).unwrap();
+    interpolator.validate().unwrap();
+
+    let unsorted: Interpolator<f64> =
+        serde_json::from_str(r#"{"x_vals": [0.0, 2.0, 1.0], "y_vals": [1.0, 2.0, 3.0]}"#).unwrap();
+    assert!(matches!(
+        unsorted.validate(),
+        Err(InterpolationError::InvalidKnots { .. })
+    ));
+    let short: Interpolator<f64> =
+        serde_json::from_str(r#"{"x_vals": [0.0, 1.0], "y_vals": [1.0]}"#).unwrap();
+    assert!(matches!(
+        short.validate(),
+        Err(InterpolationError::LengthMismatch {
+            expected: 2,
+            found: 1
+        })
+    ));
+    assert!(matches!(
+        Interpolator::<f64>::new().validate(),
+        Err(InterpolationError::InvalidKnots { .. })
+    ));
+
+    let ragged = Interpolator {
+        x_vals: vec![0., 1.],
+        y_vals: vec![vec![1., 2.], vec![3.]],
+    };
+    assert!(matches!(
+        ragged.validate(),
+        Err(InterpolationError::LengthMismatch {
+            expected: 2,
+            found: 1
+        })
+    ));
+}
+
+#[test]
+fn _interpolate_opt() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![2., 4., 6., 8., 10.],
+    };
+    assert_eq!(Some(5.), interpolator.interpolate_opt(2.5));
+    assert_eq!(None, interpolator.interpolate_opt(0.5));
+    assert_eq!(None, interpolator.interpolate_opt(6.));
+    assert_eq!(None, interpolator.interpolate_opt(f64::NAN));
+
+    let ys: Vec<f64> = [0., 1., 3., 6.]
+        .iter()
+        .filter_map(|&x| interpolator.interpolate_opt(x))
+        .collect();
+    assert_eq!(vec![2., 6.], ys);
+}
+
+#[test]
+fn _interpolate_clamped() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![2., 4., 6., 8., 10.],
+    };
+    assert_eq!(2., interpolator.interpolate_clamped(0.5));
+    assert_eq!(10., interpolator.interpolate_clamped(6.));
+    assert_eq!(5., interpolator.interpolate_clamped(2.5));
+    assert_eq!(2., interpolator.interpolate_clamped(f64::NAN));
+    assert!(Interpolator::<f64>::new().interpolate_clamped(1.).is_nan());
+}
+
+#[test]
+fn _interpolate_saturating() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![2., 4., 6., 8., 10.],
+    };
+    assert_eq!(
+        (10., Saturation::High),
+        interpolator.interpolate_saturating(6.)
+    );
+    assert_eq!(
+        (2., Saturation::Low),
+        interpolator.interpolate_saturating(0.)
+    );
+    assert_eq!(
+        (5., Saturation::None),
+        interpolator.interpolate_saturating(2.5)
+    );
+    let (y, saturation) = Interpolator::<f64>::new().interpolate_saturating(1.);
+    assert!(y.is_nan());
+    assert_eq!(Saturation::None, saturation);
+}
+
+#[test]
+fn _interpolate_weighted() {
+    let a = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![2., 4., 6., 8., 10.],
+    };
+    let b = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![1., 1., 1., 1., 1.],
+    };
+    // (3 * 5 + 1 * 1) / 4
+    let result = interpolate_weighted(&[(3., &a), (1., &b)], 2.5).unwrap();
+    assert_eq!(4., result);
+
+    let result = interpolate_weighted(&[(3., &a), (1., &b)], 6.);
+    assert!(matches!(
+        result,
+        Err(InterpolationError::OutOfBounds { .. })
+    ));
+}
+
+#[test]
+fn _nearest_knot() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![2., 4., 6., 8., 10.],
+    };
+    assert_eq!((1, 2., 4.), interpolator.nearest_knot(2.4).unwrap());
+    assert_eq!((2, 3., 6.), interpolator.nearest_knot(2.6).unwrap());
+    // Ties resolve to the lower index.
+    assert_eq!((1, 2., 4.), interpolator.nearest_knot(2.5).unwrap());
+    assert!(matches!(
+        Interpolator::new().nearest_knot(1.),
+        Err(InterpolationError::InvalidKnots { .. })
+    ));
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn _interpolate_time() {
+    use chrono::{TimeZone, Utc};
+
+    let times = [
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+    ];
+    let interpolator = TimeInterpolator::new(&times, &[10., 20.]).unwrap();
+    let midpoint = Utc.with_ymd_and_hms(2024, 1, 1, 6, 0, 0).unwrap();
+    assert_eq!(15., interpolator.interpolate(midpoint).unwrap());
+
+    let late = Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap();
+    let e = interpolator.interpolate(late).unwrap_err();
+    assert!(matches!(e, InterpolationError::TimeOutOfBounds { t, .. } if t == late));
+    assert_eq!(
+        "unable to interpolate time: 2024-01-02T00:00:00+00:00 expected within range \
+         2024-01-01T00:00:00+00:00 and 2024-01-01T12:00:00+00:00",
+        e.to_string()
+    );
+
+    // Empty or unsorted times are rejected up front.
+    assert!(matches!(
+        TimeInterpolator::new(&[], &[]),
+        Err(InterpolationError::InvalidKnots { .. })
+    ));
+    assert!(matches!(
+        TimeInterpolator::new(&[times[1], times[0]], &[10., 20.]),
+        Err(InterpolationError::InvalidKnots { .. })
+    ));
+    assert!(matches!(
+        TimeInterpolator::new(&times, &[10.]),
+        Err(InterpolationError::LengthMismatch { .. })
+    ));
+}
+
+#[test]
+fn _contains() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![2., 4., 6., 8., 10.],
+    };
+    assert!(interpolator.contains(1.));
+    assert!(interpolator.contains(5.));
+    assert!(interpolator.contains(2.5));
+    assert!(!interpolator.contains(0.5));
+    assert!(!interpolator.contains(5.5));
+    assert!(!interpolator.contains(f64::NAN));
+    assert!(!Interpolator::<f64>::new().contains(1.));
+}
+
+#[test]
+fn _accessors() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![2., 4., 6., 8., 10.],
+    };
+    assert_eq!(Some(1.), interpolator.x_min());
+    assert_eq!(Some(5.), interpolator.x_max());
+    assert_eq!(5, interpolator.len());
+    assert!(!interpolator.is_empty());
+    assert_eq!(Some((2., 10.)), interpolator.y_range());
+
+    let empty = Interpolator::<f64>::new();
+    assert!(empty.is_empty());
+    assert_eq!(None, empty.x_min());
+    assert_eq!(None, empty.y_range());
+}
+
+#[test]
+fn _normalized_position() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![2., 4., 6., 8., 10.],
+    };
+    assert_eq!(0.5, interpolator.normalized_position(3.).unwrap());
+    assert_eq!(0., interpolator.normalized_position(1.).unwrap());
+    assert!(interpolator.normalized_position(6.).is_err());
+
+    let single = Interpolator {
+        x_vals: vec![1.],
+        y_vals: vec![2.],
+    };
+    for interpolator in [Interpolator::new(), single] {
+        assert!(matches!(
+            interpolator.normalized_position(1.),
+            Err(InterpolationError::InvalidKnots { .. })
+        ));
+    }
+}
+
+#[test]
+fn _max_abs_diff() {
+    let interpolator = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![2., 4., 6., 8., 10.],
+    };
+    let shifted = Interpolator {
+        x_vals: vec![1., 2., 3., 4., 5.],
+        y_vals: vec![2., 4., 6.5, 8., 10.],
+    };
+    let samples = [1., 2.5, 3., 3.5, 5.];
+    assert_eq!(
+        0.,
+        interpolator
+            .max_abs_diff(&interpolator.clone(), &samples)
+            .unwrap()
+    );
+    assert_eq!(0.5, interpolator.max_abs_diff(&shifted, &samples).unwrap());
+    assert!(interpolator.max_abs_diff(&shifted, &[6.]).is_err());
+}
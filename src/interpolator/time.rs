@@ -0,0 +1,59 @@
+// Linear interpolator over a timestamp x-axis.
+
+use super::{InterpolationError, Interpolator};
+use chrono::{DateTime, Utc};
+
+/// 1-D linear interpolator with `DateTime<Utc>` knots.
+///
+/// Times are converted to `f64` seconds since the Unix epoch internally.
+#[derive(PartialEq, Debug, Default, Clone)]
+pub struct TimeInterpolator {
+    pub(crate) interpolator: Interpolator<f64>,
+    pub(crate) t_min: DateTime<Utc>,
+    pub(crate) t_max: DateTime<Utc>,
+}
+
+impl TimeInterpolator {
+    /// Creates an interpolator from sorted `times` and their `y_vals`.
+    /// # Errors
+    ///
+    /// `LengthMismatch` if there isn't one `y` value per time, and `InvalidKnots` if there are no times or they are
+    /// not strictly increasing.
+    pub fn new(times: &[DateTime<Utc>], y_vals: &[f64]) -> Result<Self, InterpolationError> {
+        let interpolator = Interpolator {
+            x_vals: times.iter().map(epoch_seconds).collect(),
+            y_vals: y_vals.to_vec(),
+        };
+        interpolator.validate()?;
+
+        Ok(Self {
+            interpolator,
+            t_min: times.first().copied().unwrap_or_default(),
+            t_max: times.last().copied().unwrap_or_default(),
+        })
+    }
+
+    /// Provides the interpolated value at time `t`.
+    /// # Errors
+    ///
+    /// `TimeOutOfBounds`
+    pub fn interpolate(&self, t: DateTime<Utc>) -> Result<f64, InterpolationError> {
+        match self.interpolator.interpolate(epoch_seconds(&t)) {
+            Ok((_, y)) => Ok(y),
+            Err(InterpolationError::OutOfBounds { .. }) => {
+                Err(InterpolationError::TimeOutOfBounds {
+                    t,
+                    t_min: self.t_min,
+                    t_max: self.t_max,
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+// Seconds since the Unix epoch, including the sub-second part.
+#[allow(clippy::cast_precision_loss)]
+fn epoch_seconds(t: &DateTime<Utc>) -> f64 {
+    t.timestamp() as f64 + f64::from(t.timestamp_subsec_nanos()) * 1e-9
+}
@@ -0,0 +1,58 @@
+// 1-D interpolator propagating the uncertainty of each knot.
+
+use super::{InterpolationError, Interpolator, sanity_check};
+
+/// 1-D linear interpolator with an uncertainty `y_err` for each `y` value.
+///
+/// The uncertainties of the two knots of a segment are assumed independent, so at fractional position `d`
+/// the propagated uncertainty is `sqrt((1 - d)^2 * e_lo^2 + d^2 * e_hi^2)`.
+#[derive(PartialEq, Debug, Default, Clone)]
+pub struct UncertainInterpolator {
+    interpolator: Interpolator<f64>,
+    y_err: Vec<f64>,
+}
+
+impl UncertainInterpolator {
+    /// Pairs an interpolator with the uncertainty of each of its `y` values.
+    /// # Errors
+    ///
+    /// `LengthMismatch` if `y_err` doesn't have one value per knot.
+    pub fn new(interpolator: Interpolator<f64>, y_err: &[f64]) -> Result<Self, InterpolationError> {
+        if y_err.len() != interpolator.y_vals.len() {
+            return Err(InterpolationError::LengthMismatch {
+                expected: interpolator.y_vals.len(),
+                found: y_err.len(),
+            });
+        }
+
+        Ok(Self {
+            interpolator,
+            y_err: y_err.to_vec(),
+        })
+    }
+
+    /// Provides the interpolated value and its propagated uncertainty, as `(y, y_err)`.
+    /// # Errors
+    ///
+    /// `OutOfBounds` and `NaN`
+    pub fn interpolate(&self, x: f64) -> Result<(f64, f64), InterpolationError> {
+        let x_vals = &self.interpolator.x_vals;
+        let y_vals = &self.interpolator.y_vals;
+        sanity_check(x, x_vals)?;
+
+        match x_vals.binary_search_by(|val| val.total_cmp(&x)) {
+            // Exact match found: x_vals[i] == x
+            Ok(i) => Ok((y_vals[i], self.y_err[i])),
+            // x_vals[i - 1] < x < x_vals[i]
+            Err(i) => {
+                let delta = (x - x_vals[i - 1]) / (x_vals[i] - x_vals[i - 1]);
+                let y = (1. - delta) * y_vals[i - 1] + delta * y_vals[i];
+                let y_err = ((1. - delta).powi(2) * self.y_err[i - 1].powi(2)
+                    + delta.powi(2) * self.y_err[i].powi(2))
+                .sqrt();
+
+                Ok((y, y_err))
+            }
+        }
+    }
+}
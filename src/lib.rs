@@ -1,4 +1,8 @@
 pub mod interpolator;
-pub use interpolator::Interpolator;
+pub use interpolator::{Interpolate, Interpolator};
 pub mod file_io;
 pub use file_io::*;
+pub mod grid;
+pub use grid::{Grid2D, GridInterpolator2D};
+pub mod precision;
+pub use precision::SignificantDigits;
@@ -0,0 +1,384 @@
+//! Rounding of floating point values to significant digits during serialization.
+//!
+//! Wrap any serializable data to write floats without meaningless round-off:
+//!     `serialize_json_to_path(&SignificantDigits::new(&data, 6), &"/path/to/file.json")?;`
+
+use serde::ser::{self, Serialize, Serializer};
+
+/// Serializes the wrapped value with every `f32` and `f64` rounded to a number of significant digits.
+///
+/// Integers, strings and all other values are serialized unchanged.
+#[derive(Debug, Clone, Copy)]
+pub struct SignificantDigits<'a, T: ?Sized> {
+    value: &'a T,
+    digits: usize,
+}
+
+impl<'a, T: ?Sized> SignificantDigits<'a, T> {
+    /// Wraps `value`, rounding its floats to `digits` significant digits (at least 1, and at most 17, the most an
+    /// `f64` holds).
+    #[must_use]
+    pub fn new(value: &'a T, digits: usize) -> Self {
+        Self {
+            value,
+            digits: digits.clamp(1, 17),
+        }
+    }
+}
+
+impl<T: Serialize + ?Sized> Serialize for SignificantDigits<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(Rounding {
+            inner: serializer,
+            digits: self.digits,
+        })
+    }
+}
+
+/// Rounds `value` to `digits` significant digits, leaving non-finite values unchanged.
+fn round_significant(value: f64, digits: usize) -> f64 {
+    if !value.is_finite() {
+        return value;
+    }
+    // Scientific notation holds exactly `digits` significant digits.
+    format!("{value:.*e}", digits - 1).parse().unwrap_or(value)
+}
+
+/// Forwards to the inner serializer, wrapping every nested value so its floats are rounded too.
+struct Rounding<S> {
+    inner: S,
+    digits: usize,
+}
+
+impl<S> Rounding<S> {
+    fn wrap<'a, T: ?Sized>(&self, value: &'a T) -> SignificantDigits<'a, T> {
+        SignificantDigits {
+            value,
+            digits: self.digits,
+        }
+    }
+}
+
+macro_rules! forward {
+    ($($method:ident($ty:ty)),* $(,)?) => {$(
+        fn $method(self, v: $ty) -> Result<Self::Ok, Self::Error> {
+            self.inner.$method(v)
+        }
+    )*};
+}
+
+impl<S: Serializer> Serializer for Rounding<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Rounding<S::SerializeSeq>;
+    type SerializeTuple = Rounding<S::SerializeTuple>;
+    type SerializeTupleStruct = Rounding<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Rounding<S::SerializeTupleVariant>;
+    type SerializeMap = Rounding<S::SerializeMap>;
+    type SerializeStruct = Rounding<S::SerializeStruct>;
+    type SerializeStructVariant = Rounding<S::SerializeStructVariant>;
+
+    forward!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_unit_struct(&'static str),
+    );
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.inner
+            .serialize_f32(round_significant(f64::from(v), self.digits) as f32)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_f64(round_significant(v, self.digits))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_none()
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_some(&value)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.inner
+            .serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_newtype_struct(name, &value)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        let value = self.wrap(value);
+        self.inner
+            .serialize_newtype_variant(name, variant_index, variant, &value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(Rounding {
+            inner: self.inner.serialize_seq(len)?,
+            digits: self.digits,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Ok(Rounding {
+            inner: self.inner.serialize_tuple(len)?,
+            digits: self.digits,
+        })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Ok(Rounding {
+            inner: self.inner.serialize_tuple_struct(name, len)?,
+            digits: self.digits,
+        })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(Rounding {
+            inner: self
+                .inner
+                .serialize_tuple_variant(name, variant_index, variant, len)?,
+            digits: self.digits,
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(Rounding {
+            inner: self.inner.serialize_map(len)?,
+            digits: self.digits,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(Rounding {
+            inner: self.inner.serialize_struct(name, len)?,
+            digits: self.digits,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(Rounding {
+            inner: self
+                .inner
+                .serialize_struct_variant(name, variant_index, variant, len)?,
+            digits: self.digits,
+        })
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+impl<S: ser::SerializeSeq> ser::SerializeSeq for Rounding<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_element(&value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.end()
+    }
+}
+
+impl<S: ser::SerializeTuple> ser::SerializeTuple for Rounding<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_element(&value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.end()
+    }
+}
+
+impl<S: ser::SerializeTupleStruct> ser::SerializeTupleStruct for Rounding<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_field(&value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.end()
+    }
+}
+
+impl<S: ser::SerializeTupleVariant> ser::SerializeTupleVariant for Rounding<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_field(&value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.end()
+    }
+}
+
+impl<S: ser::SerializeMap> ser::SerializeMap for Rounding<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        let key = self.wrap(key);
+        self.inner.serialize_key(&key)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_value(&value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.end()
+    }
+}
+
+impl<S: ser::SerializeStruct> ser::SerializeStruct for Rounding<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_field(key, &value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        self.inner.skip_field(key)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.end()
+    }
+}
+
+impl<S: ser::SerializeStructVariant> ser::SerializeStructVariant for Rounding<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        let value = self.wrap(value);
+        self.inner.serialize_field(key, &value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        self.inner.skip_field(key)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.end()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::Serialize;
+
+    #[test]
+    fn test_significant_digits() {
+        #[derive(Serialize)]
+        struct Sample {
+            small: f64,
+            large: f32,
+            count: u64,
+            label: String,
+            nested: Vec<Option<f64>>,
+        }
+
+        let sample = Sample {
+            small: 0.000_123_456_789,
+            large: 123_456.79,
+            count: 123_456_789,
+            label: "0.30000000000000004".to_string(),
+            nested: vec![Some(0.1 + 0.2), None, Some(f64::INFINITY)],
+        };
+        let json = serde_json::to_string(&SignificantDigits::new(&sample, 3)).unwrap();
+        assert_eq!(
+            r#"{"small":0.000123,"large":123000.0,"count":123456789,"label":"0.30000000000000004","nested":[0.3,null,null]}"#,
+            json
+        );
+
+        // Beyond the precision of an `f64`, values are unchanged.
+        let json =
+            serde_json::to_string(&SignificantDigits::new(&(0.1 + 0.2), usize::MAX)).unwrap();
+        assert_eq!("0.30000000000000004", json);
+    }
+}